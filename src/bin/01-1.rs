@@ -1,4 +1,3 @@
-use anyhow;
 use aoc24::{self};
 
 fn main() -> anyhow::Result<()> {
@@ -6,7 +5,7 @@ fn main() -> anyhow::Result<()> {
 
     let (mut l1, mut l2) = lines
         .map(|line| {
-            let mut parts = line.trim().split_whitespace();
+            let mut parts = line.split_whitespace();
             let n1: i32 = parts.next().unwrap().parse().unwrap();
             let n2: i32 = parts.next().unwrap().parse().unwrap();
             (n1, n2)
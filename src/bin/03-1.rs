@@ -1,20 +1,11 @@
-use anyhow;
+use aoc24::scan::{self, InstructionShape, Interpreter};
 use aoc24::{self};
-use regex::Regex;
 
 fn main() -> anyhow::Result<()> {
     let input = aoc24::read_input_file();
-    // matches mul(123,4) and captures 123 and 4
-    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    let res: u64 = re
-        .captures_iter(&input)
-        .map(|cap| {
-            let (_, [s1, s2]) = cap.extract();
-            let n1: u64 = s1.parse().unwrap();
-            let n2: u64 = s2.parse().unwrap();
-            n1 * n2
-        })
-        .sum();
-    println!("{}", res);
+    let run = Interpreter::new()
+        .instruction(InstructionShape::new("mul", 2, 1..=3), scan::mul)
+        .run(input.as_bytes());
+    println!("{}", run.state.total);
     Ok(())
 }
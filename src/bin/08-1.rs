@@ -1,6 +1,5 @@
 use std::collections::{HashMap, HashSet};
 
-use anyhow;
 use aoc24::{self, Location};
 use itertools::*;
 
@@ -1,6 +1,5 @@
 use std::collections::{HashMap, HashSet};
 
-use anyhow;
 use aoc24::{self, Location};
 use itertools::*;
 
@@ -39,7 +38,8 @@ fn main() -> anyhow::Result<()> {
     for (_, ant_locations) in location_map.iter() {
         let pairs = ant_locations.iter().cartesian_product(ant_locations.iter());
         for (loc1, loc2) in pairs.filter(|(l1, l2)| l1 != l2) {
-            let distance = loc2.distance(loc1);
+            // step by the reduced distance to hit every grid position in line
+            let distance = loc2.distance(loc1).reduced();
 
             for i in 0.. {
                 let new_antinode = loc1.apply_n_distance(&distance, -i);
@@ -59,7 +59,7 @@ fn main() -> anyhow::Result<()> {
             }
         }
     }
-    println!("{}", antinode_locations.iter().count());
+    println!("{}", antinode_locations.len());
 
     Ok(())
 }
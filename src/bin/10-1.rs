@@ -1,5 +1,3 @@
-use anyhow;
-use aoc24;
 use itertools::Itertools;
 use petgraph::algo::DfsSpace;
 use petgraph::dot;
@@ -1,9 +1,6 @@
 use std::marker::PhantomData;
 
-use anyhow;
-use aoc24;
 use itertools::Itertools;
-use petgraph::algo::DfsSpace;
 use petgraph::dot;
 use petgraph::prelude::*;
 
@@ -12,7 +9,7 @@ struct Discard<T> {
 }
 
 impl<T> FromIterator<T> for Discard<T> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    fn from_iter<I: IntoIterator<Item = T>>(_iter: I) -> Self {
         /* noop */
         Discard {
             _marker: PhantomData,
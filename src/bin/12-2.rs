@@ -1,9 +1,5 @@
-use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
-    iter::once,
-};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use anyhow;
 use aoc24::{self, Distance, Location};
 use itertools::Itertools;
 use rayon::prelude::*;
@@ -168,25 +164,13 @@ fn main() -> anyhow::Result<()> {
     let lines = aoc24::read_input_lines();
     let field = aoc24::read_visual_map(lines);
 
-    // use floodfill to generate ids of connected locations
-
-    let mut loc_rest: BTreeSet<Location> = field.map.keys().cloned().collect();
-    let mut connected_locs: Vec<HashSet<Location>> = Vec::new();
-    while let Some(loc) = loc_rest.pop_first() {
-        let c = field.map.get(&loc).unwrap();
-        let mut stack = vec![loc];
-        let mut found: HashSet<Location> = once(loc).collect();
-        while let Some(l) = stack.pop() {
-            for l_test in [l.up(), l.down(), l.left(), l.right()] {
-                if field.map.get(&l_test) == Some(&c) && !found.contains(&l_test) {
-                    stack.push(l_test);
-                    found.insert(l_test);
-                    loc_rest.remove(&l_test);
-                }
-            }
-        }
-        connected_locs.push(found);
-    }
+    // use union-find to generate ids of connected locations
+    let connected_locs: Vec<HashSet<Location>> = field
+        .union_find()
+        .components()
+        .into_iter()
+        .map(|locs| locs.into_iter().collect())
+        .collect();
 
     println!("Number of regions: {}", connected_locs.len());
 
@@ -220,31 +204,31 @@ fn main() -> anyhow::Result<()> {
 
     let mut corner_count_by_id: HashMap<usize, usize> = HashMap::new();
     for loc in field.map.keys() {
-        let id = ids.get(loc).unwrap().clone();
+        let id = *ids.get(loc).unwrap();
         let corner_count = corner_count_by_id.entry(id).or_default();
 
-        if upper_left_outer_corners.get(loc).is_some() {
+        if upper_left_outer_corners.contains_key(loc) {
             *corner_count += 1;
         }
-        if upper_right_outer_corners.get(loc).is_some() {
+        if upper_right_outer_corners.contains_key(loc) {
             *corner_count += 1;
         }
-        if lower_left_outer_corners.get(loc).is_some() {
+        if lower_left_outer_corners.contains_key(loc) {
             *corner_count += 1;
         }
-        if lower_right_outer_corners.get(loc).is_some() {
+        if lower_right_outer_corners.contains_key(loc) {
             *corner_count += 1;
         }
-        if upper_left_inner_corners.get(loc).is_some() {
+        if upper_left_inner_corners.contains_key(loc) {
             *corner_count += 1;
         }
-        if upper_right_inner_corners.get(loc).is_some() {
+        if upper_right_inner_corners.contains_key(loc) {
             *corner_count += 1;
         }
-        if lower_left_inner_corners.get(loc).is_some() {
+        if lower_left_inner_corners.contains_key(loc) {
             *corner_count += 1;
         }
-        if lower_right_inner_corners.get(loc).is_some() {
+        if lower_right_inner_corners.contains_key(loc) {
             *corner_count += 1;
         }
     }
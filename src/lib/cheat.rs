@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use rayon::prelude::*;
+
+use crate::{Location, VisualMap};
+
+/// Steps from `start` to every location reachable through locations allowed by `passable`
+///
+/// ```
+/// # use aoc24::cheat::distance_map;
+/// # use aoc24::Location;
+/// let map = aoc24::read_visual_map(["S.#", "#..", "..."].into_iter().map(String::from));
+/// let dist = distance_map(&map, Location::new(0, 0), |c| *c != '#');
+/// assert_eq!(dist[&Location::new(2, 0)], 4);
+/// assert!(!dist.contains_key(&Location::new(0, 2)));
+/// ```
+pub fn distance_map<T>(
+    map: &VisualMap<T>,
+    start: Location,
+    passable: impl Fn(&T) -> bool,
+) -> HashMap<Location, usize> {
+    let mut dist = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(loc) = queue.pop_front() {
+        let d = dist[&loc];
+        for next in [loc.up(), loc.down(), loc.left(), loc.right()] {
+            if !dist.contains_key(&next) && map.map.get(&next).is_some_and(&passable) {
+                dist.insert(next, d + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    dist
+}
+
+/// A race track from `S` to `E` between `#` walls, with the distances of every
+/// track location to both ends.
+///
+/// ```
+/// # use aoc24::cheat::Track;
+/// let map = aoc24::read_visual_map(
+///     [
+///         "###############",
+///         "#...#...#.....#",
+///         "#.#.#.#.#.###.#",
+///         "#S#...#.#.#...#",
+///         "#######.#.#.###",
+///         "#######.#.#...#",
+///         "#######.#.###.#",
+///         "###..E#...#...#",
+///         "###.#######.###",
+///         "#...###...#...#",
+///         "#.#####.#.###.#",
+///         "#.#...#.#.#...#",
+///         "#.#.#.#.#.#.###",
+///         "#...#...#...###",
+///         "###############",
+///     ]
+///     .into_iter()
+///     .map(String::from),
+/// );
+/// let track = Track::new(&map).unwrap();
+/// assert_eq!(track.best(), 84);
+/// let savings = track.savings(2);
+/// assert_eq!(savings[&2], 14);
+/// assert_eq!(savings[&64], 1);
+/// assert_eq!(savings.values().sum::<usize>(), 44);
+/// assert_eq!(track.count_savings(20, 50), 285);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Track {
+    from_start: HashMap<Location, usize>,
+    to_end: HashMap<Location, usize>,
+    best: usize,
+}
+
+impl Track {
+    /// Compute the distance maps, `None` if the map has no `S`, no `E` or no way between them
+    pub fn new(map: &VisualMap<char>) -> Option<Self> {
+        let find = |c| map.map.iter().find(|(_, v)| **v == c).map(|(loc, _)| *loc);
+        let (start, end) = (find('S')?, find('E')?);
+        let from_start = distance_map(map, start, |c| *c != '#');
+        let to_end = distance_map(map, end, |c| *c != '#');
+        let best = *from_start.get(&end)?;
+        Some(Track {
+            from_start,
+            to_end,
+            best,
+        })
+    }
+
+    /// Length of the shortest way without cheating
+    #[inline(always)]
+    pub fn best(&self) -> usize {
+        self.best
+    }
+
+    /// Histogram of how many cheats save how many steps, for cheats of at most
+    /// `radius` steps through walls that save at least one step.
+    ///
+    /// A cheat goes from one track location to another within `radius`, and
+    /// is identified by these two locations. The track locations are checked
+    /// in parallel.
+    pub fn savings(&self, radius: usize) -> BTreeMap<usize, usize> {
+        let cells: Vec<(&Location, &usize)> = self.from_start.iter().collect();
+        cells
+            .into_par_iter()
+            .fold(BTreeMap::new, |mut histogram, (from, to_here)| {
+                for to in from.diamond(radius) {
+                    let Some(rest) = self.to_end.get(&to) else {
+                        continue;
+                    };
+                    let length = to_here + from.manhattan(&to) + rest;
+                    if length < self.best {
+                        *histogram.entry(self.best - length).or_insert(0) += 1;
+                    }
+                }
+                histogram
+            })
+            .reduce(BTreeMap::new, |mut a, b| {
+                for (saved, count) in b {
+                    *a.entry(saved).or_insert(0) += count;
+                }
+                a
+            })
+    }
+
+    /// Number of cheats of at most `radius` steps saving at least `min_saved` steps
+    pub fn count_savings(&self, radius: usize, min_saved: usize) -> usize {
+        self.savings(radius)
+            .range(min_saved.max(1)..)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
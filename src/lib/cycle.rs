@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Find the cycle of the sequence `initial, step(initial), step(step(initial)), ...`
+/// using Brent's algorithm.
+///
+/// Returns `(mu, lambda)`, where `mu` is the index of the first state that is
+/// part of the cycle and `lambda` is the length of the cycle. Only `Eq` is
+/// needed on the state and only a constant number of states is kept in memory.
+///
+/// ```
+/// # use aoc24::cycle::find_cycle;
+/// // 3, 9, 81, 61, 21, 41, 81, ...
+/// assert_eq!(find_cycle(3_u32, |&n| n * n % 100), (2, 4));
+/// ```
+///
+/// ```
+/// # use aoc24::cycle::find_cycle;
+/// # use aoc24::{Direction, Location};
+/// // a guard turning right at the corners of a square
+/// let step = |&(loc, dir): &(Location, Direction)| {
+///     let next = loc.apply(dir);
+///     if next.is_inside_bounding_box(&Location::new(0, 0), &Location::new(2, 2)) {
+///         (next, dir)
+///     } else {
+///         (loc, dir.turn_right())
+///     }
+/// };
+/// assert_eq!(find_cycle((Location::new(1, 1), Direction::Up), step), (2, 12));
+/// ```
+pub fn find_cycle<S: Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    // find the cycle length by teleporting the tortoise at powers of two
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    // find the start of the cycle with two pointers `lambda` apart
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..lambda {
+        hare = step(&hare);
+    }
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+    (mu, lambda)
+}
+
+/// Compute the `n`th state of the sequence, skipping whole cycles.
+///
+/// ```
+/// # use aoc24::cycle::nth_state;
+/// let step = |&n: &u64| n * n % 100;
+/// assert_eq!(nth_state(3, step, 0), 3);
+/// assert_eq!(nth_state(3, step, 5), 41);
+/// assert_eq!(nth_state(3, step, 1_000_000_000), 21);
+/// ```
+pub fn nth_state<S: Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (mu, lambda) = find_cycle(initial.clone(), &mut step);
+    let n = if n < mu { n } else { mu + (n - mu) % lambda };
+    let mut state = initial;
+    for _ in 0..n {
+        state = step(&state);
+    }
+    state
+}
+
+/// A cycle found by remembering every state, see `find_cycle_hashed`.
+#[derive(Debug, Clone)]
+pub struct Cycle<S> {
+    /// Index of the first state that is part of the cycle
+    pub mu: usize,
+    /// Length of the cycle
+    pub lambda: usize,
+    /// All states up to the end of the first pass through the cycle
+    pub states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// The `n`th state of the sequence, without simulating again
+    pub fn nth(&self, n: usize) -> &S {
+        if n < self.mu {
+            &self.states[n]
+        } else {
+            &self.states[self.mu + (n - self.mu) % self.lambda]
+        }
+    }
+}
+
+/// Find the cycle by hashing every state, stepping only once per state.
+///
+/// Prefer this over `find_cycle` if `step` is expensive and the states are small.
+///
+/// ```
+/// # use aoc24::cycle::find_cycle_hashed;
+/// let cycle = find_cycle_hashed(3_u32, |&n| n * n % 100);
+/// assert_eq!((cycle.mu, cycle.lambda), (2, 4));
+/// assert_eq!(cycle.states, vec![3, 9, 81, 61, 21, 41]);
+/// assert_eq!(*cycle.nth(1_000_000_000), 21);
+/// ```
+pub fn find_cycle_hashed<S: Hash + Eq + Clone>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+) -> Cycle<S> {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(&mu) = seen.get(&state) {
+            return Cycle {
+                mu,
+                lambda: states.len() - mu,
+                states,
+            };
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
@@ -0,0 +1,213 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Context;
+
+/// A file occupying the blocks `start..start + len`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct File {
+    pub id: usize,
+    pub start: usize,
+    pub len: usize,
+}
+
+impl File {
+    /// Sum of block position times file id over all blocks of the file
+    pub fn checksum(&self) -> u64 {
+        (self.start..self.start + self.len).sum::<usize>() as u64 * self.id as u64
+    }
+}
+
+/// A disk as single blocks, each holding a file id or being free
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Blocks(pub Vec<Option<usize>>);
+
+impl Blocks {
+    /// Move blocks one at a time from the end of the disk into the leftmost
+    /// free block, until there are no gaps left
+    ///
+    /// ```
+    /// # use aoc24::disk::Disk;
+    /// let disk: Disk = "12345".parse().unwrap();
+    /// let mut blocks = disk.blocks();
+    /// assert_eq!(blocks.to_string(), "0..111....22222");
+    /// blocks.compact();
+    /// assert_eq!(blocks.to_string(), "022111222......");
+    /// ```
+    pub fn compact(&mut self) {
+        let blocks = &mut self.0;
+        let (mut free, mut last) = (0, blocks.len());
+        loop {
+            while free < last && blocks[free].is_some() {
+                free += 1;
+            }
+            while last > free && blocks[last - 1].is_none() {
+                last -= 1;
+            }
+            if last <= free + 1 {
+                return;
+            }
+            blocks.swap(free, last - 1);
+        }
+    }
+
+    /// Sum of block position times file id over all used blocks
+    pub fn checksum(&self) -> u64 {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, id)| Some((pos * (*id)?) as u64))
+            .sum()
+    }
+}
+
+impl fmt::Display for Blocks {
+    /// Draw free blocks as `.` and used blocks as the last digit of their file id
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for block in &self.0 {
+            let c = match block {
+                Some(id) => char::from_digit((id % 10) as u32, 10).unwrap(),
+                None => '.',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// A disk as files and free spans, read from a dense disk map of digits
+/// alternating between file and free space lengths.
+///
+/// ```
+/// # use aoc24::disk::Disk;
+/// let disk: Disk = "2333133121414131402".parse().unwrap();
+/// assert_eq!(disk.to_string(), "00...111...2...333.44.5555.6666.777.888899");
+///
+/// let mut blocks = disk.blocks();
+/// blocks.compact();
+/// assert_eq!(blocks.to_string(), "0099811188827773336446555566..............");
+/// assert_eq!(blocks.checksum(), 1928);
+///
+/// let compacted = disk.compact_files();
+/// assert_eq!(compacted.to_string(), "00992111777.44.333....5555.6666.....8888..");
+/// assert_eq!(compacted.checksum(), 2858);
+///
+/// // free space around zero length files forms a single span
+/// let disk: Disk = "0229046867".parse().unwrap();
+/// assert_eq!(disk.free, vec![(0, 2), (4, 13), (23, 8), (37, 7)]);
+/// assert_eq!(disk.compact_files().checksum(), 382);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Disk {
+    /// Files ordered by id
+    pub files: Vec<File>,
+    /// Free spans as `(start, len)`, ordered by start
+    pub free: Vec<(usize, usize)>,
+    /// Total number of blocks
+    pub size: usize,
+}
+
+impl FromStr for Disk {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut disk = Disk {
+            files: vec![],
+            free: vec![],
+            size: 0,
+        };
+        for (i, c) in s.trim().chars().enumerate() {
+            let len = c
+                .to_digit(10)
+                .with_context(|| format!("invalid length {:?} at {}", c, i))?
+                as usize;
+            if i % 2 == 0 {
+                disk.files.push(File {
+                    id: i / 2,
+                    start: disk.size,
+                    len,
+                });
+            } else if len > 0 {
+                // a zero length file in between must not split the free space
+                match disk.free.last_mut() {
+                    Some((start, free)) if *start + *free == disk.size => *free += len,
+                    _ => disk.free.push((disk.size, len)),
+                }
+            }
+            disk.size += len;
+        }
+        Ok(disk)
+    }
+}
+
+impl Disk {
+    pub fn blocks(&self) -> Blocks {
+        let mut blocks = vec![None; self.size];
+        for file in &self.files {
+            blocks[file.start..file.start + file.len].fill(Some(file.id));
+        }
+        Blocks(blocks)
+    }
+
+    /// Move each file once, in order of decreasing id, into the leftmost free
+    /// span it fits into, if that is left of the file.
+    ///
+    /// Free spans are kept in min-heaps of start positions keyed by span
+    /// length, so this runs in O(n log n).
+    pub fn compact_files(&self) -> Disk {
+        let mut buckets: BTreeMap<usize, BinaryHeap<Reverse<usize>>> = BTreeMap::new();
+        for &(start, len) in &self.free {
+            buckets.entry(len).or_default().push(Reverse(start));
+        }
+
+        let mut files = self.files.clone();
+        for file in files.iter_mut().rev() {
+            let best = buckets
+                .range(file.len..)
+                .filter_map(|(len, starts)| Some((starts.peek()?.0, *len)))
+                .filter(|(start, _)| *start < file.start)
+                .min();
+            let Some((start, len)) = best else {
+                continue;
+            };
+            buckets.get_mut(&len).unwrap().pop();
+            if len > file.len {
+                buckets
+                    .entry(len - file.len)
+                    .or_default()
+                    .push(Reverse(start + file.len));
+            }
+            // the freed span is right of all files still to be moved, so it
+            // never needs to be in a bucket
+            file.start = start;
+        }
+
+        let mut used: Vec<(usize, usize)> = files.iter().map(|f| (f.start, f.len)).collect();
+        used.sort();
+        let mut free = vec![];
+        let mut pos = 0;
+        for (start, len) in used.into_iter().chain([(self.size, 0)]) {
+            if start > pos {
+                free.push((pos, start - pos));
+            }
+            pos = pos.max(start + len);
+        }
+        Disk {
+            files,
+            free,
+            size: self.size,
+        }
+    }
+
+    pub fn checksum(&self) -> u64 {
+        self.files.iter().map(File::checksum).sum()
+    }
+}
+
+impl fmt::Display for Disk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.blocks())
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Context;
+
+use crate::math;
+
+/// A binary operator of an equation, evaluated strictly left to right.
+pub trait Operator: fmt::Debug + Send + Sync {
+    /// Compute `lhs op rhs`, `None` on overflow
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64>;
+
+    /// Find `lhs` with `lhs op rhs == result`.
+    ///
+    /// This allows the solver to work from right to left and prune early.
+    fn inverse(&self, result: u64, rhs: u64) -> Inverse;
+
+    /// The symbol used when displaying a solution
+    fn symbol(&self) -> &'static str;
+}
+
+/// The values of `lhs` solving `lhs op rhs == result`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inverse {
+    /// No `lhs` works
+    None,
+    /// Exactly this `lhs` works
+    Exact(u64),
+    /// Every `lhs` works, like for `lhs * 0 == 0`
+    Any,
+}
+
+impl From<Option<u64>> for Inverse {
+    fn from(lhs: Option<u64>) -> Self {
+        lhs.map_or(Inverse::None, Inverse::Exact)
+    }
+}
+
+/// Addition, `a + b`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Add;
+
+/// ```
+/// # use aoc24::equation::{Add, Inverse, Operator};
+/// assert_eq!(Add.apply(u64::MAX - 1, 1), Some(u64::MAX));
+/// assert_eq!(Add.apply(u64::MAX, 1), None);
+/// assert_eq!(Add.inverse(5, 7), Inverse::None);
+/// assert_eq!(Add.inverse(u64::MAX, u64::MAX), Inverse::Exact(0));
+/// ```
+impl Operator for Add {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.checked_add(rhs)
+    }
+
+    fn inverse(&self, result: u64, rhs: u64) -> Inverse {
+        result.checked_sub(rhs).into()
+    }
+
+    fn symbol(&self) -> &'static str {
+        "+"
+    }
+}
+
+/// Multiplication, `a * b`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mul;
+
+/// ```
+/// # use aoc24::equation::{Inverse, Mul, Operator};
+/// assert_eq!(Mul.apply(u64::MAX, 1), Some(u64::MAX));
+/// assert_eq!(Mul.apply(1 << 32, 1 << 32), None);
+/// assert_eq!(Mul.apply(0, u64::MAX), Some(0));
+/// assert_eq!(Mul.inverse(12, 5), Inverse::None);
+/// assert_eq!(Mul.inverse(12, 0), Inverse::None);
+/// assert_eq!(Mul.inverse(0, 0), Inverse::Any);
+/// assert_eq!(Mul.inverse(0, 5), Inverse::Exact(0));
+/// ```
+impl Operator for Mul {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.checked_mul(rhs)
+    }
+
+    /// Only exact divisions are inverted. A zero `rhs` does not determine `lhs`,
+    /// any `lhs` works if `result` is zero as well.
+    fn inverse(&self, result: u64, rhs: u64) -> Inverse {
+        if rhs == 0 {
+            return if result == 0 {
+                Inverse::Any
+            } else {
+                Inverse::None
+            };
+        }
+        result.is_multiple_of(rhs).then(|| result / rhs).into()
+    }
+
+    fn symbol(&self) -> &'static str {
+        "*"
+    }
+}
+
+/// Concatenation of the decimal digits, `12 || 345 = 12345`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Concat;
+
+/// ```
+/// # use aoc24::equation::{Concat, Inverse, Operator};
+/// assert_eq!(Concat.apply(12, 345), Some(12345));
+/// assert_eq!(Concat.apply(12, 0), Some(120));
+/// assert_eq!(Concat.apply(0, 7), Some(7));
+/// assert_eq!(Concat.apply(1, u64::MAX), None);
+/// assert_eq!(Concat.apply(0, u64::MAX), Some(u64::MAX));
+/// assert_eq!(Concat.inverse(12345, 345), Inverse::Exact(12));
+/// assert_eq!(Concat.inverse(120, 0), Inverse::Exact(12));
+/// assert_eq!(Concat.inverse(12345, 45), Inverse::Exact(123));
+/// assert_eq!(Concat.inverse(12345, 44), Inverse::None);
+/// assert_eq!(Concat.inverse(u64::MAX, u64::MAX), Inverse::Exact(0));
+/// assert_eq!(Concat.inverse(u64::MAX, 10_000_000_000_000_000_000), Inverse::None);
+/// ```
+impl Operator for Concat {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        math::checked_concat(lhs, rhs)
+    }
+
+    /// Strip the digits of `rhs` from the end of `result`
+    fn inverse(&self, result: u64, rhs: u64) -> Inverse {
+        match 10_u64.checked_pow(math::decimal_digits(rhs)) {
+            Some(shift) => (result % shift == rhs).then(|| result / shift).into(),
+            // `rhs` has as many digits as any u64 can have, nothing is left for `lhs`
+            None => (result == rhs).then_some(0).into(),
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        "||"
+    }
+}
+
+/// A calibration equation like `190: 10 19`, with the operators left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equation {
+    pub target: u64,
+    pub numbers: Vec<u64>,
+}
+
+impl FromStr for Equation {
+    type Err = anyhow::Error;
+
+    /// Parse `target: n1 n2 ...`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (target, numbers) = s.split_once(':').context("missing ':'")?;
+        let target = target.trim().parse()?;
+        let numbers = numbers
+            .split_whitespace()
+            .map(|n| n.parse())
+            .collect::<Result<_, _>>()?;
+        Ok(Equation { target, numbers })
+    }
+}
+
+/// The operators that make an equation true, one between each pair of numbers
+#[derive(Debug, Clone)]
+pub struct Solution<'a> {
+    pub numbers: Vec<u64>,
+    pub operators: Vec<&'a dyn Operator>,
+}
+
+impl fmt::Display for Solution<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut numbers = self.numbers.iter();
+        if let Some(n) = numbers.next() {
+            write!(f, "{}", n)?;
+        }
+        for (op, n) in self.operators.iter().zip(numbers) {
+            write!(f, " {} {}", op.symbol(), n)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find operators that make equations true, searching from right to left.
+///
+/// Since the equation is evaluated left to right, the last operator can be
+/// undone from the target. Every operator that can not be inverted prunes
+/// the whole branch. If any left hand side works, like for `x * 0 == 0`, the
+/// remaining numbers only need some choice of operators that does not overflow.
+///
+/// ```
+/// # use aoc24::equation::{Add, Concat, Equation, EquationSolver, Mul};
+/// let input = "\
+/// 190: 10 19
+/// 3267: 81 40 27
+/// 83: 17 5
+/// 156: 15 6
+/// 7290: 6 8 6 15
+/// 161011: 16 10 13
+/// 192: 17 8 14
+/// 21037: 9 7 18 13
+/// 292: 11 6 16 20";
+/// let equations: Vec<Equation> = input.lines().map(|l| l.parse().unwrap()).collect();
+/// let part1 = EquationSolver::new(vec![Box::new(Add), Box::new(Mul)]);
+/// let part2 = EquationSolver::new(vec![Box::new(Add), Box::new(Mul), Box::new(Concat)]);
+/// let calibration = |solver: &EquationSolver| -> u64 {
+///     equations.iter().filter(|e| solver.is_solvable(e)).map(|e| e.target).sum()
+/// };
+/// assert_eq!(calibration(&part1), 3749);
+/// assert_eq!(calibration(&part2), 11387);
+/// assert_eq!(part2.solve(&equations[4]).unwrap().to_string(), "6 * 8 || 6 * 15");
+/// ```
+///
+/// Targets and numbers near `u64::MAX` and zeros are handled exactly:
+///
+/// ```
+/// # use aoc24::equation::{Add, Concat, Equation, EquationSolver, Mul};
+/// let solver = EquationSolver::new(vec![Box::new(Add), Box::new(Mul), Box::new(Concat)]);
+/// let max = u64::MAX;
+/// assert!(solver.is_solvable(&format!("{}: {} 1 1", max, max - 2).parse().unwrap()));
+/// assert!(solver.is_solvable(&format!("{}: 1844674407370955161 5", max).parse().unwrap()));
+/// assert!(!solver.is_solvable(&format!("{}: {} 2", max - 1, max).parse().unwrap()));
+/// assert!(solver.is_solvable(&"0: 0 0".parse().unwrap()));
+/// assert!(solver.is_solvable(&"100: 10 0".parse().unwrap()));
+/// assert!(solver.is_solvable(&"5: 0 5".parse().unwrap()));
+/// assert!(!solver.is_solvable(&"1: 0 0".parse().unwrap()));
+/// assert!(solver.is_solvable(&"0: 5 0".parse().unwrap()));
+/// assert_eq!(solver.solve(&"7: 3 0 7".parse().unwrap()).unwrap().to_string(), "3 * 0 + 7");
+/// let part1 = EquationSolver::new(vec![Box::new(Add), Box::new(Mul)]);
+/// assert!(part1.is_solvable(&format!("0: {} 1 0", max).parse().unwrap()));
+/// assert!(!part1.is_solvable(&format!("0: {} 2 0", max).parse().unwrap()));
+/// ```
+#[derive(Debug)]
+pub struct EquationSolver {
+    operators: Vec<Box<dyn Operator>>,
+}
+
+impl EquationSolver {
+    pub fn new(operators: Vec<Box<dyn Operator>>) -> Self {
+        EquationSolver { operators }
+    }
+
+    /// Find one choice of operators making the equation true
+    pub fn solve(&self, equation: &Equation) -> Option<Solution<'_>> {
+        let mut operators = Vec::new();
+        self.solve_rec(equation.target, &equation.numbers, &mut operators)
+            .then(|| {
+                operators.reverse();
+                Solution {
+                    numbers: equation.numbers.clone(),
+                    operators,
+                }
+            })
+    }
+
+    #[inline(always)]
+    pub fn is_solvable(&self, equation: &Equation) -> bool {
+        self.solve(equation).is_some()
+    }
+
+    /// Collects the operators from right to left into `operators`
+    fn solve_rec<'a>(
+        &'a self,
+        target: u64,
+        numbers: &[u64],
+        operators: &mut Vec<&'a dyn Operator>,
+    ) -> bool {
+        match numbers {
+            [] => false,
+            [n] => *n == target,
+            [rest @ .., n] => {
+                for op in &self.operators {
+                    operators.push(op.as_ref());
+                    let solved = match op.inverse(target, *n) {
+                        Inverse::None => false,
+                        Inverse::Exact(lhs) => self.solve_rec(lhs, rest, operators),
+                        Inverse::Any => {
+                            let mut prefix = Vec::new();
+                            let solved = self.evaluate_rec(rest[0], &rest[1..], &mut prefix);
+                            operators.extend(prefix.into_iter().rev());
+                            solved
+                        }
+                    };
+                    if solved {
+                        return true;
+                    }
+                    operators.pop();
+                }
+                false
+            }
+        }
+    }
+
+    /// Find any operators evaluating `acc` followed by `numbers` without
+    /// overflow, collecting them from left to right into `operators`
+    fn evaluate_rec<'a>(
+        &'a self,
+        acc: u64,
+        numbers: &[u64],
+        operators: &mut Vec<&'a dyn Operator>,
+    ) -> bool {
+        let Some((n, rest)) = numbers.split_first() else {
+            return true;
+        };
+        for op in &self.operators {
+            if let Some(next) = op.apply(acc, *n) {
+                operators.push(op.as_ref());
+                if self.evaluate_rec(next, rest, operators) {
+                    return true;
+                }
+                operators.pop();
+            }
+        }
+        false
+    }
+
+    /// Evaluate the numbers with the given operators from left to right, `None` on overflow
+    ///
+    /// ```
+    /// # use aoc24::equation::{Concat, EquationSolver, Mul};
+    /// assert_eq!(EquationSolver::evaluate(&[6, 8, 6], &[&Mul, &Concat]), Some(486));
+    /// assert_eq!(EquationSolver::evaluate(&[u64::MAX, 2], &[&Mul]), None);
+    /// ```
+    pub fn evaluate(numbers: &[u64], operators: &[&dyn Operator]) -> Option<u64> {
+        let mut numbers = numbers.iter();
+        let first = numbers.next().copied().unwrap_or(0);
+        numbers
+            .zip(operators)
+            .try_fold(first, |acc, (n, op)| op.apply(acc, *n))
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+pub use regex::{Captures, Regex};
+
+/// Why a value could not be extracted, with the byte span in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtractError {
+    /// The regex did not match the input at all
+    NoMatch { input: String },
+    /// The named group did not take part in the match spanning `span`
+    MissingGroup {
+        group: &'static str,
+        span: Range<usize>,
+    },
+    /// The text of the named group at `span` could not be parsed
+    Parse {
+        group: &'static str,
+        text: String,
+        span: Range<usize>,
+        message: String,
+    },
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractError::NoMatch { input } => write!(f, "no match in {:?}", input),
+            ExtractError::MissingGroup { group, span } => {
+                write!(f, "group {:?} missing in match at {:?}", group, span)
+            }
+            ExtractError::Parse {
+                group,
+                text,
+                span,
+                message,
+            } => write!(
+                f,
+                "could not parse group {:?} = {:?} at {:?}: {}",
+                group, text, span, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExtractError {}
+
+/// Types that can be built from the named groups of a regex match.
+///
+/// Usually implemented with the `from_captures!` macro.
+pub trait FromCaptures: Sized {
+    fn from_captures(caps: &Captures) -> Result<Self, ExtractError>;
+}
+
+/// Parse the named group `name` of a match
+///
+/// ```
+/// # use aoc24::extract::{capture, ExtractError};
+/// let re = regex::Regex::new(r"x=(?P<x>\S+)(?: y=(?P<y>\S+))?").unwrap();
+/// let caps = re.captures("at x=12").unwrap();
+/// assert_eq!(capture::<i32>(&caps, "x"), Ok(12));
+/// assert_eq!(
+///     capture::<i32>(&caps, "y"),
+///     Err(ExtractError::MissingGroup { group: "y", span: 3..7 })
+/// );
+/// let caps = re.captures("at x=1z").unwrap();
+/// assert!(matches!(capture::<i32>(&caps, "x"), Err(ExtractError::Parse { span, .. }) if span == (5..7)));
+/// ```
+pub fn capture<T>(caps: &Captures, name: &'static str) -> Result<T, ExtractError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let m = caps.name(name).ok_or_else(|| ExtractError::MissingGroup {
+        group: name,
+        span: caps.get(0).unwrap().range(),
+    })?;
+    m.as_str().parse().map_err(|e: T::Err| ExtractError::Parse {
+        group: name,
+        text: m.as_str().to_string(),
+        span: m.range(),
+        message: e.to_string(),
+    })
+}
+
+/// Extract a value from the first match of `re` in `input`
+pub fn extract<T: FromCaptures>(re: &Regex, input: &str) -> Result<T, ExtractError> {
+    let caps = re.captures(input).ok_or_else(|| ExtractError::NoMatch {
+        input: input.to_string(),
+    })?;
+    T::from_captures(&caps)
+}
+
+/// Extract a value from every match of `re` in `input`
+pub fn extract_all<'a, T: FromCaptures + 'a>(
+    re: &'a Regex,
+    input: &'a str,
+) -> impl Iterator<Item = Result<T, ExtractError>> + 'a {
+    re.captures_iter(input).map(|caps| T::from_captures(&caps))
+}
+
+/// Implement `FromCaptures` for a struct, filling each listed field from the
+/// named group of the same name.
+///
+/// ```
+/// use aoc24::extract::{extract, extract_all};
+/// use aoc24::from_captures;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Button {
+///     name: char,
+///     x: i64,
+///     y: i64,
+/// }
+/// from_captures!(Button { name, x, y });
+///
+/// let re = regex::Regex::new(r"Button (?P<name>\w): X\+(?P<x>\d+), Y\+(?P<y>\d+)").unwrap();
+/// let button: Button = extract(&re, "Button A: X+94, Y+34").unwrap();
+/// assert_eq!(button, Button { name: 'A', x: 94, y: 34 });
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Robot {
+///     px: i64,
+///     py: i64,
+///     vx: i64,
+///     vy: i64,
+/// }
+/// from_captures!(Robot { px, py, vx, vy });
+///
+/// let re = regex::Regex::new(r"p=(?P<px>-?\d+),(?P<py>-?\d+) v=(?P<vx>-?\d+),(?P<vy>-?\d+)")
+///     .unwrap();
+/// let robots: Vec<Robot> = extract_all(&re, "p=0,4 v=3,-3\np=6,3 v=-1,-3")
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(robots[1], Robot { px: 6, py: 3, vx: -1, vy: -3 });
+///
+/// let err = extract::<Button>(&re, "Button A: X+94, Y+34").unwrap_err();
+/// assert_eq!(err.to_string(), "no match in \"Button A: X+94, Y+34\"");
+/// ```
+#[macro_export]
+macro_rules! from_captures {
+    ($ty:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::extract::FromCaptures for $ty {
+            fn from_captures(
+                caps: &$crate::extract::Captures,
+            ) -> ::std::result::Result<Self, $crate::extract::ExtractError> {
+                Ok($ty {
+                    $($field: $crate::extract::capture(caps, stringify!($field))?,)*
+                })
+            }
+        }
+    };
+}
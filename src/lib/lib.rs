@@ -1,16 +1,36 @@
 use std::collections::BTreeMap;
+use std::fmt;
 #[allow(dead_code)]
 use std::fs::read_to_string;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::str::FromStr;
+
+pub mod cheat;
+pub mod cycle;
+pub mod disk;
+pub mod equation;
+pub mod extract;
+pub mod math;
+pub mod memo;
+pub mod multiset;
+pub mod order;
+pub mod patrol;
+pub mod pattern;
+pub mod region;
+pub mod report;
+pub mod robots;
+pub mod scan;
+pub mod search;
+pub mod simulation;
+pub mod trie;
+pub mod union_find;
+pub mod vm;
+pub mod warehouse;
 
 pub fn input_file() -> String {
-    let input_file = std::env::args()
-        .skip(1)
-        .next()
-        .expect("Expected input FILE");
-    input_file
+    std::env::args().nth(1).expect("Expected input FILE")
 }
 
 pub fn read_input_file() -> String {
@@ -20,28 +40,110 @@ pub fn read_input_file() -> String {
 
 pub fn read_input_lines() -> impl Iterator<Item = String> {
     let input_file = input_file();
-    let lines = BufReader::new(File::open(input_file).expect("Could not open FILE"))
+    BufReader::new(File::open(input_file).expect("Could not open FILE"))
         .lines()
-        .map(|line| line.expect("Could not read line"));
-    lines
+        .map(|line| line.expect("Could not read line"))
 }
 
 pub fn read_file_lines(file: &str) -> impl Iterator<Item = String> {
     let input_file = file;
-    let lines = BufReader::new(File::open(input_file).expect("Could not open FILE"))
+    BufReader::new(File::open(input_file).expect("Could not open FILE"))
         .lines()
-        .map(|line| line.expect("Could not read line"));
-    lines
+        .map(|line| line.expect("Could not read line"))
 }
 
-#[derive(Debug, Clone)]
+/// Split lines into sections separated by blank lines.
+///
+/// Several blank lines in a row count as one separator.
+///
+/// ```
+/// let lines = "47|53\n97|13\n\n75,47,61\n\n\n97,61".lines().map(String::from);
+/// let sections = aoc24::split_sections(lines);
+/// assert_eq!(sections, vec![vec!["47|53", "97|13"], vec!["75,47,61"], vec!["97,61"]]);
+/// ```
+pub fn split_sections(lines: impl Iterator<Item = String>) -> Vec<Vec<String>> {
+    let mut sections = vec![Vec::new()];
+    for line in lines {
+        if line.trim().is_empty() {
+            if !sections.last().unwrap().is_empty() {
+                sections.push(Vec::new());
+            }
+        } else {
+            sections.last_mut().unwrap().push(line);
+        }
+    }
+    if sections.last().unwrap().is_empty() {
+        sections.pop();
+    }
+    sections
+}
+
+pub fn read_sections() -> Vec<Vec<String>> {
+    split_sections(read_input_lines())
+}
+
+/// Parse each blank line separated section as one record, with its lines joined by `\n`.
+///
+/// ```
+/// #[derive(Debug, PartialEq)]
+/// struct Block(usize);
+///
+/// impl std::str::FromStr for Block {
+///     type Err = std::convert::Infallible;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         Ok(Block(s.lines().count()))
+///     }
+/// }
+///
+/// let lines = "#####\n.####\n\n.....\n#....\n#...#".lines().map(String::from);
+/// let blocks: Vec<Block> = aoc24::parse_records(lines).unwrap();
+/// assert_eq!(blocks, vec![Block(2), Block(3)]);
+/// ```
+pub fn parse_records<T: FromStr>(lines: impl Iterator<Item = String>) -> Result<Vec<T>, T::Err> {
+    split_sections(lines)
+        .into_iter()
+        .map(|section| section.join("\n").parse())
+        .collect()
+}
+
+pub fn read_records<T: FromStr>() -> Vec<T>
+where
+    T::Err: fmt::Debug,
+{
+    parse_records(read_input_lines()).expect("Could not parse record")
+}
+
+/// Parse a map section followed by a section of lines parsed by `f`.
+///
+/// ```
+/// let lines = "#@O.\n#...\n\n<^^>\nv<".lines().map(String::from);
+/// let (map, moves) = aoc24::parse_map_and_lines(lines, |line| line.chars().collect::<Vec<_>>());
+/// assert_eq!(map.max, aoc24::Location::new(1, 3));
+/// assert_eq!(moves.concat().len(), 6);
+/// ```
+pub fn parse_map_and_lines<T>(
+    lines: impl Iterator<Item = String>,
+    f: impl Fn(&str) -> T,
+) -> (VisualMap<char>, Vec<T>) {
+    let mut sections = split_sections(lines).into_iter();
+    let map = read_visual_map(sections.next().unwrap_or_default().into_iter());
+    let rest = sections.flatten().map(|line| f(&line)).collect();
+    (map, rest)
+}
+
+pub fn read_map_and_lines<T>(f: impl Fn(&str) -> T) -> (VisualMap<char>, Vec<T>) {
+    parse_map_and_lines(read_input_lines(), f)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct VisualMap<T> {
     pub map: BTreeMap<Location, T>,
     pub max: Location,
 }
 
 pub fn read_visual_map(lines: impl Iterator<Item = String>) -> VisualMap<char> {
-    read_visual_map_filter_map(lines, |c| Some(c))
+    read_visual_map_filter_map(lines, Some)
 }
 
 pub fn read_visual_map_filter_map<T>(
@@ -64,6 +166,36 @@ pub fn read_visual_map_filter_map<T>(
     VisualMap { map, max }
 }
 
+impl<T> VisualMap<T> {
+    /// Render the map row by row from (0, 0) to `max`, using `f` to draw each location
+    ///
+    /// ```
+    /// let map = aoc24::read_visual_map_filter_map(
+    ///     ["#..", ".#."].into_iter().map(String::from),
+    ///     |c| (c == '#').then_some(()),
+    /// );
+    /// assert_eq!(map.render(|_, v| if v.is_some() { '#' } else { ' ' }), "#  \n # \n");
+    /// ```
+    pub fn render(&self, f: impl Fn(&Location, Option<&T>) -> char) -> String {
+        let mut out = String::new();
+        for row in 0..=self.max.row {
+            for col in 0..=self.max.col {
+                let loc = Location::new(row, col);
+                out.push(f(&loc, self.map.get(&loc)));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl fmt::Display for VisualMap<char> {
+    /// Render the map as it was read, drawing missing locations as `.`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|_, c| *c.unwrap_or(&'.')))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Location {
     pub row: isize,
@@ -215,6 +347,35 @@ impl Location {
         }
     }
 
+    /// The number of steps between two locations when only moving up, down, left or right
+    ///
+    /// ```
+    /// # use aoc24::Location;
+    /// assert_eq!(Location::new(1, 1).manhattan(&Location::new(-2, 3)), 5);
+    /// ```
+    #[inline(always)]
+    pub fn manhattan(&self, other: &Location) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    /// All locations with a manhattan distance of at most `radius`, including this one
+    ///
+    /// ```
+    /// # use aoc24::Location;
+    /// let center = Location::new(5, 5);
+    /// assert_eq!(center.diamond(0).collect::<Vec<_>>(), vec![center]);
+    /// assert_eq!(center.diamond(2).count(), 13);
+    /// assert!(center.diamond(20).all(|loc| loc.manhattan(&center) <= 20));
+    /// ```
+    pub fn diamond(&self, radius: usize) -> impl Iterator<Item = Location> {
+        let center = *self;
+        let r = radius as isize;
+        (-r..=r).flat_map(move |drow| {
+            let width = r - drow.abs();
+            (-width..=width).map(move |dcol| Location::new(center.row + drow, center.col + dcol))
+        })
+    }
+
     /// Create a new location at one step in the given direction
     ///
     /// ```
@@ -370,12 +531,44 @@ impl Direction {
             Left => Right,
         }
     }
-}
 
-impl Into<Distance> for Direction {
-    fn into(self) -> Distance {
+    /// Turn the direction clockwise by a quarter turn.
+    ///
+    /// ```
+    /// # use aoc24::Direction;
+    /// assert_eq!(Direction::Up.turn_right(), Direction::Right);
+    /// assert_eq!(Direction::Right.turn_right(), Direction::Down);
+    /// assert_eq!(Direction::Down.turn_right(), Direction::Left);
+    /// assert_eq!(Direction::Left.turn_right(), Direction::Up);
+    /// ```
+    #[inline(always)]
+    pub fn turn_right(&self) -> Direction {
         use Direction::*;
         match self {
+            Up => Right,
+            Right => Down,
+            Down => Left,
+            Left => Up,
+        }
+    }
+
+    /// Turn the direction counterclockwise by a quarter turn.
+    ///
+    /// ```
+    /// # use aoc24::Direction;
+    /// assert_eq!(Direction::Up.turn_left(), Direction::Left);
+    /// assert_eq!(Direction::Left.turn_left(), Direction::Down);
+    /// ```
+    #[inline(always)]
+    pub fn turn_left(&self) -> Direction {
+        self.turn_right().rev()
+    }
+}
+
+impl From<Direction> for Distance {
+    fn from(dir: Direction) -> Distance {
+        use Direction::*;
+        match dir {
             Up => Distance::new(-1, 0),
             Down => Distance::new(1, 0),
             Right => Distance::new(0, 1),
@@ -402,4 +595,24 @@ impl Distance {
     pub fn new(row: isize, col: isize) -> Self {
         Distance { row, col }
     }
+
+    /// Create the smallest distance pointing in the same direction,
+    /// dividing both components by their greatest common divisor
+    ///
+    /// ```
+    /// # use aoc24::Distance;
+    /// assert_eq!(Distance::new(-4, 6).reduced(), Distance::new(-2, 3));
+    /// assert_eq!(Distance::new(0, 5).reduced(), Distance::new(0, 1));
+    /// assert_eq!(Distance::new(0, 0).reduced(), Distance::new(0, 0));
+    /// ```
+    pub fn reduced(&self) -> Self {
+        let g = math::gcd(self.row, self.col);
+        if g == 0 {
+            return *self;
+        }
+        Distance {
+            row: self.row / g,
+            col: self.col / g,
+        }
+    }
 }
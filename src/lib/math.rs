@@ -0,0 +1,609 @@
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+use crate::Location;
+
+/// The primitive integer operations needed by the functions in this module
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_rem_euclid(self, other: Self) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
+}
+
+/// Integers with a sign, needed for the extended Euclidean algorithm
+pub trait SignedInteger: Integer {
+    fn checked_neg(self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline(always)]
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                #[inline(always)]
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                #[inline(always)]
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                #[inline(always)]
+                fn checked_rem_euclid(self, other: Self) -> Option<Self> {
+                    <$t>::checked_rem_euclid(self, other)
+                }
+
+                #[inline(always)]
+                #[allow(unused_comparisons)]
+                fn checked_abs(self) -> Option<Self> {
+                    if self < 0 {
+                        <$t>::checked_sub(0, self)
+                    } else {
+                        Some(self)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed_integer {
+    ($($t:ty),*) => {
+        $(
+            impl SignedInteger for $t {
+                #[inline(always)]
+                fn checked_neg(self) -> Option<Self> {
+                    <$t>::checked_neg(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_signed_integer!(i8, i16, i32, i64, i128, isize);
+
+/// Greatest common divisor, always non-negative.
+///
+/// Panics if the result does not fit into `T`, which only happens for `T::MIN`.
+///
+/// ```
+/// # use aoc24::math::gcd;
+/// assert_eq!(gcd(12, 18), 6);
+/// assert_eq!(gcd(-4_i32, 6), 2);
+/// assert_eq!(gcd(0_u8, 7), 7);
+/// assert_eq!(gcd(0, 0), 0);
+/// ```
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a.checked_abs().expect("gcd overflows")
+}
+
+/// Least common multiple, `None` on overflow.
+///
+/// ```
+/// # use aoc24::math::checked_lcm;
+/// assert_eq!(checked_lcm(4, 6), Some(12));
+/// assert_eq!(checked_lcm(0, 6), Some(0));
+/// assert_eq!(checked_lcm(200_u8, 3), None);
+/// ```
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b)?.checked_abs()
+}
+
+/// Least common multiple, panics on overflow.
+///
+/// ```
+/// # use aoc24::math::lcm;
+/// assert_eq!([2_u64, 3, 4, 5].into_iter().fold(1, lcm), 60);
+/// ```
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflows")
+}
+
+/// Extended Euclidean algorithm.
+///
+/// Returns `(g, x, y)` with `g = gcd(a, b) >= 0` and `a * x + b * y = g`.
+///
+/// ```
+/// # use aoc24::math::ext_gcd;
+/// let (g, x, y) = ext_gcd(240, 46);
+/// assert_eq!(g, 2);
+/// assert_eq!(240 * x + 46 * y, 2);
+/// let (g, x, y) = ext_gcd(-6_i64, 4);
+/// assert_eq!((g, -6 * x + 4 * y), (2, 2));
+/// ```
+pub fn ext_gcd<T: SignedInteger>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < T::ZERO {
+        let neg = |v: T| v.checked_neg().expect("ext_gcd overflows");
+        (neg(old_r), neg(old_x), neg(old_y))
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m`, in `0..m`.
+///
+/// `None` if `a` and `m` are not coprime or `m` is not positive.
+///
+/// ```
+/// # use aoc24::math::mod_inverse;
+/// assert_eq!(mod_inverse(3, 11), Some(4));
+/// assert_eq!(mod_inverse(-3, 11), Some(7));
+/// assert_eq!(mod_inverse(4, 8), None);
+/// ```
+pub fn mod_inverse<T: SignedInteger>(a: T, m: T) -> Option<T> {
+    if m <= T::ZERO {
+        return None;
+    }
+    let (g, x, _) = ext_gcd(a.checked_rem_euclid(m)?, m);
+    (g == T::ONE).then(|| x.checked_rem_euclid(m)).flatten()
+}
+
+/// Solve a system of congruences `x = r (mod m)` with the Chinese Remainder Theorem.
+///
+/// The moduli do not need to be coprime. Returns `(x, m)` with `x` in `0..m`,
+/// `m` being the lcm of all moduli, so all solutions are `x + k * m`.
+/// `None` if the system has no solution or an intermediate result overflows `T`.
+///
+/// ```
+/// # use aoc24::math::crt;
+/// assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+/// assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+/// ```
+pub fn crt<T: SignedInteger>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut x = T::ZERO;
+    let mut m = T::ONE;
+    for &(r, n) in congruences {
+        if n <= T::ZERO {
+            return None;
+        }
+        let r = r.checked_rem_euclid(n)?;
+        let g = gcd(m, n);
+        let diff = r.checked_sub(x)?;
+        if diff % g != T::ZERO {
+            return None;
+        }
+        // x + m * k = r (mod n)  <=>  (m / g) * k = diff / g (mod n / g)
+        let n_g = n / g;
+        let k = (diff / g)
+            .checked_rem_euclid(n_g)?
+            .checked_mul(mod_inverse(m / g, n_g)?)?
+            .checked_rem_euclid(n_g)?;
+        let new_m = (m / g).checked_mul(n)?;
+        x = x
+            .checked_add(m.checked_mul(k)?)?
+            .checked_rem_euclid(new_m)?;
+        m = new_m;
+    }
+    Some((x, m))
+}
+
+/// Number of decimal digits of `n`, zero has one digit.
+///
+/// ```
+/// # use aoc24::math::decimal_digits;
+/// assert_eq!(decimal_digits(0_u64), 1);
+/// assert_eq!(decimal_digits(9_u64), 1);
+/// assert_eq!(decimal_digits(10_u64), 2);
+/// assert_eq!(decimal_digits(999_999_999_999_999_999_u64), 18);
+/// assert_eq!(decimal_digits(1_000_000_000_000_000_000_u64), 19);
+/// assert_eq!(decimal_digits(u64::MAX), 20);
+/// assert_eq!(decimal_digits(u128::MAX), 39);
+/// ```
+#[inline(always)]
+pub fn decimal_digits(n: impl Into<u128>) -> u32 {
+    n.into().checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Concatenate the decimal digits of `lhs` and `rhs`, `None` on overflow.
+///
+/// ```
+/// # use aoc24::math::checked_concat;
+/// assert_eq!(checked_concat(15, 6), Some(156));
+/// assert_eq!(checked_concat(15, 0), Some(150));
+/// assert_eq!(checked_concat(0, 0), Some(0));
+/// assert_eq!(checked_concat(1844674407370955161, 5), Some(u64::MAX));
+/// assert_eq!(checked_concat(1844674407370955161, 6), None);
+/// assert_eq!(checked_concat(1, 10_000_000_000_000_000_000), None);
+/// ```
+pub fn checked_concat(lhs: u64, rhs: u64) -> Option<u64> {
+    match 10_u64.checked_pow(decimal_digits(rhs)) {
+        Some(shift) => lhs.checked_mul(shift)?.checked_add(rhs),
+        None => (lhs == 0).then_some(rhs),
+    }
+}
+
+/// Wrap `value` into `0..modulus`, also for negative values.
+///
+/// ```
+/// # use aoc24::math::wrap;
+/// assert_eq!(wrap(-1, 7), 6);
+/// assert_eq!(wrap(15, 7), 1);
+/// ```
+#[inline(always)]
+pub fn wrap<T: Integer>(value: T, modulus: T) -> T {
+    value
+        .checked_rem_euclid(modulus)
+        .expect("modulus must not be zero")
+}
+
+/// Wrap a location onto a torus of the given size, with `(0, 0)` as upper left corner.
+///
+/// ```
+/// # use aoc24::math::wrap_location;
+/// # use aoc24::Location;
+/// assert_eq!(wrap_location(&Location::new(-1, 12), 7, 11), Location::new(6, 1));
+/// ```
+#[inline(always)]
+pub fn wrap_location(loc: &Location, height: isize, width: isize) -> Location {
+    Location::new(wrap(loc.row, height), wrap(loc.col, width))
+}
+
+/// An exact fraction of two `i128`, always kept in lowest terms with a positive denominator.
+///
+/// Arithmetic panics on overflow instead of silently losing precision.
+///
+/// ```
+/// # use aoc24::math::Rational;
+/// let a = Rational::new(1, 3);
+/// let b = Rational::new(-2, 4);
+/// assert_eq!(b, Rational::new(1, -2));
+/// assert_eq!(a + b, Rational::new(-1, 6));
+/// assert_eq!(a * Rational::from(3), Rational::from(1));
+/// assert_eq!((a / b).to_string(), "-2/3");
+/// assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// Create the fraction `num / den`, panics if `den` is zero
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "denominator must not be zero");
+        let g = gcd(num, den);
+        let (num, den) = (num / g, den / g);
+        if den < 0 {
+            Rational {
+                num: num.checked_neg().expect("rational overflow"),
+                den: den.checked_neg().expect("rational overflow"),
+            }
+        } else {
+            Rational { num, den }
+        }
+    }
+
+    #[inline(always)]
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    #[inline(always)]
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    #[inline(always)]
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    #[inline(always)]
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    /// The value as integer, if it is one
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    /// The multiplicative inverse, panics for zero
+    ///
+    /// ```should_panic
+    /// # use aoc24::math::Rational;
+    /// let _ = Rational::from(i128::MIN).recip();
+    /// ```
+    pub fn recip(&self) -> Self {
+        Rational::new(self.den, self.num)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Rational { num: n, den: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational::from(n as i128)
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(n: i32) -> Self {
+        Rational::from(n as i128)
+    }
+}
+
+impl std::fmt::Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let lhs = self.num.checked_mul(other.den).expect("rational overflow");
+        let rhs = other.num.checked_mul(self.den).expect("rational overflow");
+        lhs.cmp(&rhs)
+    }
+}
+
+impl std::ops::Neg for Rational {
+    type Output = Rational;
+
+    /// ```should_panic
+    /// # use aoc24::math::Rational;
+    /// let _ = -Rational::from(i128::MIN);
+    /// ```
+    fn neg(self) -> Rational {
+        Rational {
+            num: self.num.checked_neg().expect("rational overflow"),
+            den: self.den,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        let g = gcd(self.den, other.den);
+        let overflow = || -> Option<Rational> {
+            let num = self
+                .num
+                .checked_mul(other.den / g)?
+                .checked_add(other.num.checked_mul(self.den / g)?)?;
+            let den = (self.den / g).checked_mul(other.den)?;
+            Some(Rational::new(num, den))
+        };
+        overflow().expect("rational overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        // cancel crosswise first to keep the intermediate values small
+        let g1 = gcd(self.num, other.den).max(1);
+        let g2 = gcd(other.num, self.den).max(1);
+        let num = (self.num / g1).checked_mul(other.num / g2);
+        let den = (self.den / g2).checked_mul(other.den / g1);
+        match (num, den) {
+            (Some(num), Some(den)) => Rational::new(num, den),
+            _ => panic!("rational overflow"),
+        }
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Rational) -> Rational {
+        self * other.recip()
+    }
+}
+
+/// The solution set of a linear system `A x = b`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearSolution {
+    /// The system is inconsistent
+    None,
+    /// Exactly one solution
+    Unique(Vec<Rational>),
+    /// All solutions are `particular + sum(t_i * directions[i])` for any values `t_i`
+    Infinite {
+        particular: Vec<Rational>,
+        directions: Vec<Vec<Rational>>,
+    },
+}
+
+impl LinearSolution {
+    /// The unique solution, if there is one and it is integral
+    pub fn integer(&self) -> Option<Vec<i128>> {
+        match self {
+            LinearSolution::Unique(x) => x.iter().map(Rational::to_integer).collect(),
+            _ => None,
+        }
+    }
+
+    /// The unique solution, if there is one and it is integral and non-negative
+    pub fn non_negative_integer(&self) -> Option<Vec<i128>> {
+        self.integer().filter(|x| x.iter().all(|&v| v >= 0))
+    }
+}
+
+/// Solve the linear system `A x = b` exactly by Gaussian elimination.
+///
+/// `a` holds the rows of the coefficient matrix, each of the same length.
+///
+/// ```
+/// # use aoc24::math::{solve_linear, LinearSolution, Rational};
+/// // Button A: X+94, Y+34; Button B: X+22, Y+67; Prize: X=8400, Y=5400
+/// let presses = solve_linear(&[[94_i64, 22], [34, 67]], &[8400, 5400]);
+/// assert_eq!(presses.non_negative_integer(), Some(vec![80, 40]));
+///
+/// // Button A: X+26, Y+66; Button B: X+67, Y+21; Prize: X=12748, Y=12176
+/// let presses = solve_linear(&[[26_i64, 67], [66, 21]], &[12748, 12176]);
+/// assert!(matches!(presses, LinearSolution::Unique(_)));
+/// assert_eq!(presses.integer(), None);
+///
+/// assert_eq!(solve_linear(&[[1_i64, 1], [1, 1]], &[1, 2]), LinearSolution::None);
+/// assert_eq!(
+///     solve_linear(&[[1_i64, 2], [2, 4]], &[3, 6]),
+///     LinearSolution::Infinite {
+///         particular: vec![Rational::from(3), Rational::from(0)],
+///         directions: vec![vec![Rational::from(-2), Rational::from(1)]],
+///     }
+/// );
+/// ```
+pub fn solve_linear<T, R>(a: &[R], b: &[T]) -> LinearSolution
+where
+    T: Copy + Into<i128>,
+    R: AsRef<[T]>,
+{
+    assert_eq!(a.len(), b.len(), "every row needs a right hand side");
+    let cols = a.first().map_or(0, |row| row.as_ref().len());
+    let mut m: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| {
+            assert_eq!(row.as_ref().len(), cols, "rows must have equal length");
+            row.as_ref()
+                .iter()
+                .chain(std::iter::once(&rhs))
+                .map(|&v| Rational::from(v.into()))
+                .collect()
+        })
+        .collect();
+
+    // reduced row echelon form
+    let mut pivots: Vec<usize> = Vec::new();
+    for col in 0..cols {
+        let r = pivots.len();
+        let Some(p) = (r..m.len()).find(|&i| !m[i][col].is_zero()) else {
+            continue;
+        };
+        m.swap(r, p);
+        let pivot = m[r][col];
+        for v in m[r].iter_mut() {
+            *v = *v / pivot;
+        }
+        let pivot_row = m[r].clone();
+        for (i, row) in m.iter_mut().enumerate() {
+            if i != r && !row[col].is_zero() {
+                let factor = row[col];
+                for (v, p) in row.iter_mut().zip(&pivot_row).skip(col) {
+                    *v = *v - factor * *p;
+                }
+            }
+        }
+        pivots.push(col);
+    }
+
+    if m[pivots.len()..].iter().any(|row| !row[cols].is_zero()) {
+        return LinearSolution::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; cols];
+    for (r, &col) in pivots.iter().enumerate() {
+        particular[col] = m[r][cols];
+    }
+    if pivots.len() == cols {
+        return LinearSolution::Unique(particular);
+    }
+
+    let directions = (0..cols)
+        .filter(|c| !pivots.contains(c))
+        .map(|free| {
+            let mut d = vec![Rational::ZERO; cols];
+            d[free] = Rational::ONE;
+            for (r, &col) in pivots.iter().enumerate() {
+                d[col] = -m[r][free];
+            }
+            d
+        })
+        .collect();
+    LinearSolution::Infinite {
+        particular,
+        directions,
+    }
+}
+
+/// Solve a 2x2 system with Cramer's rule, without any division until the end.
+///
+/// ```
+/// # use aoc24::math::{solve_2x2, LinearSolution};
+/// let presses = solve_2x2([[94, 22], [34, 67]], [8400, 5400]);
+/// assert_eq!(presses.non_negative_integer(), Some(vec![80, 40]));
+/// ```
+pub fn solve_2x2(a: [[i64; 2]; 2], b: [i64; 2]) -> LinearSolution {
+    let [[a11, a12], [a21, a22]] = a.map(|row| row.map(i128::from));
+    let [b1, b2] = b.map(i128::from);
+    let det = a11 * a22 - a12 * a21;
+    if det == 0 {
+        return solve_linear(&a, &b);
+    }
+    LinearSolution::Unique(vec![
+        Rational::new(b1 * a22 - a12 * b2, det),
+        Rational::new(a11 * b2 - b1 * a21, det),
+    ])
+}
@@ -0,0 +1,144 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// Cache statistics of a `Memo`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+impl MemoStats {
+    /// Fraction of calls answered from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let calls = self.hits + self.misses;
+        if calls == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / calls as f64
+    }
+}
+
+/// A memoized recursive function.
+///
+/// The wrapped function gets a callback for the recursive calls as its first
+/// argument, which goes through the cache again.
+///
+/// ```
+/// # use aoc24::memo::Memo;
+/// // number of stones after blinking `n` times at a stone
+/// let mut stones = Memo::new(|rec: &mut dyn FnMut((u64, u32)) -> u64, (stone, n): (u64, u32)| {
+///     if n == 0 {
+///         return 1;
+///     }
+///     if stone == 0 {
+///         return rec((1, n - 1));
+///     }
+///     let digits = stone.ilog10() + 1;
+///     if digits % 2 == 0 {
+///         let m = 10_u64.pow(digits / 2);
+///         rec((stone / m, n - 1)) + rec((stone % m, n - 1))
+///     } else {
+///         rec((stone * 2024, n - 1))
+///     }
+/// });
+/// assert_eq!(stones.call((125, 25)) + stones.call((17, 25)), 55312);
+/// assert!(stones.stats().hits > 0);
+/// ```
+pub struct Memo<K, V, F> {
+    f: Rc<F>,
+    cache: HashMap<K, V>,
+    capacity: Option<usize>,
+    order: VecDeque<K>,
+    stats: MemoStats,
+}
+
+impl<K, V, F> Memo<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    /// Wrap `f` with an unbounded cache
+    pub fn new(f: F) -> Self {
+        Memo {
+            f: Rc::new(f),
+            cache: HashMap::new(),
+            capacity: None,
+            order: VecDeque::new(),
+            stats: MemoStats::default(),
+        }
+    }
+
+    /// Wrap `f` with a cache of at most `capacity` entries, evicting the oldest entry first
+    ///
+    /// ```
+    /// # use aoc24::memo::Memo;
+    /// let mut fib = Memo::bounded(
+    ///     |rec: &mut dyn FnMut(u64) -> u64, n: u64| if n < 2 { n } else { rec(n - 1) + rec(n - 2) },
+    ///     3,
+    /// );
+    /// assert_eq!(fib.call(50), 12586269025);
+    /// assert_eq!(fib.len(), 3);
+    /// assert_eq!(fib.stats().evictions, 48);
+    /// ```
+    pub fn bounded(f: F, capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be positive");
+        Memo {
+            capacity: Some(capacity),
+            ..Self::new(f)
+        }
+    }
+
+    /// Evaluate the function at `key`, using the cache where possible
+    pub fn call(&mut self, key: K) -> V {
+        if let Some(v) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return v.clone();
+        }
+        self.stats.misses += 1;
+        let f = Rc::clone(&self.f);
+        let v = f(&mut |k| self.call(k), key.clone());
+        self.insert(key, v.clone());
+        v
+    }
+
+    fn insert(&mut self, key: K, v: V) {
+        if let Some(capacity) = self.capacity {
+            while self.cache.len() >= capacity {
+                let Some(oldest) = self.order.pop_front() else {
+                    break;
+                };
+                self.cache.remove(&oldest);
+                self.stats.evictions += 1;
+            }
+            self.order.push_back(key.clone());
+        }
+        self.cache.insert(key, v);
+    }
+
+    #[inline(always)]
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// Number of cached entries
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Drop all cached entries and reset the statistics
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+        self.stats = MemoStats::default();
+    }
+}
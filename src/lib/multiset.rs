@@ -0,0 +1,159 @@
+use std::collections::{hash_map, HashMap};
+use std::hash::Hash;
+
+/// A multiset counting how often each value occurs.
+///
+/// ```
+/// # use aoc24::multiset::Counter;
+/// let right: Counter<i32> = [4, 3, 5, 3, 9, 3].into_iter().collect();
+/// assert_eq!(right.get(&3), 3);
+/// assert_eq!(right.get(&1), 0);
+/// assert_eq!(right.len(), 4);
+/// assert_eq!(right.total(), 6);
+/// let similarity: u64 = [3, 4, 2, 1, 3, 3].iter().map(|n| *n as u64 * right.get(n)).sum();
+/// assert_eq!(similarity, 31);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, u64>,
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Add `count` occurrences of `value`
+    pub fn add(&mut self, value: T, count: u64) {
+        if count > 0 {
+            *self.counts.entry(value).or_insert(0) += count;
+        }
+    }
+
+    /// Add a single occurrence of `value`
+    #[inline(always)]
+    pub fn insert(&mut self, value: T) {
+        self.add(value, 1);
+    }
+
+    /// How often `value` occurs
+    #[inline(always)]
+    pub fn get(&self, value: &T) -> u64 {
+        self.counts.get(value).copied().unwrap_or(0)
+    }
+
+    /// Number of distinct values
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Number of values, counting each occurrence
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// The distinct values with their counts, in arbitrary order
+    pub fn iter(&self) -> hash_map::Iter<'_, T, u64> {
+        self.counts.iter()
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: Eq + Hash> IntoIterator for Counter<T> {
+    type Item = (T, u64);
+    type IntoIter = hash_map::IntoIter<T, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<'a, T: Eq + Hash> IntoIterator for &'a Counter<T> {
+    type Item = (&'a T, &'a u64);
+    type IntoIter = hash_map::Iter<'a, T, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.iter()
+    }
+}
+
+/// The result of evolving a counter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evolution<T: Eq + Hash> {
+    pub counter: Counter<T>,
+    /// Number of distinct values before the first and after each step
+    pub distinct: Vec<usize>,
+}
+
+/// Replace every value by the values `rule` turns it into, `steps` times.
+///
+/// Equal values are only expanded once per step, so the work depends on the
+/// number of distinct values instead of the total count.
+///
+/// ```
+/// # use aoc24::multiset::{evolve, Counter};
+/// fn blink(stone: u64) -> Vec<u64> {
+///     let digits = aoc24::math::decimal_digits(stone);
+///     if stone == 0 {
+///         vec![1]
+///     } else if digits % 2 == 0 {
+///         let half = 10_u64.pow(digits / 2);
+///         vec![stone / half, stone % half]
+///     } else {
+///         vec![stone * 2024]
+///     }
+/// }
+/// let stones: Counter<u64> = [125, 17].into_iter().collect();
+/// assert_eq!(evolve(&stones, blink, 6).counter.total(), 22);
+/// let evolution = evolve(&stones, blink, 25);
+/// assert_eq!(evolution.counter.total(), 55312);
+/// assert_eq!(evolution.distinct[..4], [2, 3, 4, 5]);
+/// ```
+pub fn evolve<T, I>(counter: &Counter<T>, rule: impl Fn(T) -> I, steps: usize) -> Evolution<T>
+where
+    T: Clone + Eq + Hash,
+    I: IntoIterator<Item = T>,
+{
+    let mut counter = counter.clone();
+    let mut distinct = vec![counter.len()];
+    for _ in 0..steps {
+        let mut next = Counter::new();
+        for (value, count) in counter {
+            for produced in rule(value) {
+                next.add(produced, count);
+            }
+        }
+        counter = next;
+        distinct.push(counter.len());
+    }
+    Evolution { counter, distinct }
+}
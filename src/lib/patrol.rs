@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+use crate::{Direction, Location, VisualMap};
+
+/// How a patrol ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The guard walked off the map, the location is the last one on the map
+    Exit(Location),
+    /// The guard came back to a state it was in before
+    Loop(Location, Direction),
+}
+
+/// The walk of a guard, ending when it leaves the map or starts repeating
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patrol {
+    /// Every state of the guard in order, turning in place adds a state
+    pub path: Vec<(Location, Direction)>,
+    pub outcome: Outcome,
+}
+
+impl Patrol {
+    /// All distinct locations the guard was at
+    pub fn visited(&self) -> HashSet<Location> {
+        self.path.iter().map(|(loc, _)| *loc).collect()
+    }
+
+    #[inline(always)]
+    pub fn is_loop(&self) -> bool {
+        matches!(self.outcome, Outcome::Loop(..))
+    }
+}
+
+/// Find the guard, drawn as one of `^`, `>`, `v` or `<`
+pub fn find_guard(map: &VisualMap<char>) -> Option<(Location, Direction)> {
+    map.map.iter().find_map(|(loc, c)| {
+        let dir = match c {
+            '^' => Direction::Up,
+            '>' => Direction::Right,
+            'v' => Direction::Down,
+            '<' => Direction::Left,
+            _ => return None,
+        };
+        Some((*loc, dir))
+    })
+}
+
+/// A guard walking straight ahead and turning right in front of `#`.
+///
+/// ```
+/// # use aoc24::patrol::{find_guard, Guard};
+/// let map = aoc24::read_visual_map(
+///     "....#.....\n.........#\n..........\n..#.......\n.......#..\n\
+///      ..........\n.#..^.....\n........#.\n#.........\n......#..."
+///         .lines()
+///         .map(String::from),
+/// );
+/// let (start, dir) = find_guard(&map).unwrap();
+/// let guard = Guard::new(&map);
+/// let patrol = guard.patrol(start, dir);
+/// assert!(!patrol.is_loop());
+/// assert_eq!(patrol.visited().len(), 41);
+/// assert_eq!(guard.loop_obstructions(start, dir).len(), 6);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Guard<'a> {
+    map: &'a VisualMap<char>,
+}
+
+impl<'a> Guard<'a> {
+    pub fn new(map: &'a VisualMap<char>) -> Self {
+        Guard { map }
+    }
+
+    #[inline(always)]
+    fn is_inside(&self, loc: &Location) -> bool {
+        loc.is_inside_bounding_box(&Location::new(0, 0), &self.map.max)
+    }
+
+    #[inline(always)]
+    fn is_blocked(&self, loc: &Location, extra: Option<Location>) -> bool {
+        extra == Some(*loc) || self.map.map.get(loc) == Some(&'#')
+    }
+
+    /// Walk from `start` until the guard leaves the map or loops
+    pub fn patrol(&self, start: Location, dir: Direction) -> Patrol {
+        let mut path = vec![];
+        let mut seen = HashSet::new();
+        let (mut loc, mut dir) = (start, dir);
+        loop {
+            if !seen.insert((loc, dir)) {
+                return Patrol {
+                    path,
+                    outcome: Outcome::Loop(loc, dir),
+                };
+            }
+            path.push((loc, dir));
+            let next = loc.apply(dir);
+            if !self.is_inside(&next) {
+                return Patrol {
+                    path,
+                    outcome: Outcome::Exit(loc),
+                };
+            }
+            if self.is_blocked(&next, None) {
+                dir = dir.turn_right();
+            } else {
+                loc = next;
+            }
+        }
+    }
+
+    /// Check if the guard loops with an extra obstacle, only remembering the
+    /// states where it turns
+    fn loops_with(&self, start: Location, dir: Direction, obstacle: Location) -> bool {
+        let mut turns = HashSet::new();
+        let (mut loc, mut dir) = (start, dir);
+        loop {
+            let next = loc.apply(dir);
+            if !self.is_inside(&next) {
+                return false;
+            }
+            if self.is_blocked(&next, Some(obstacle)) {
+                if !turns.insert((loc, dir)) {
+                    return true;
+                }
+                dir = dir.turn_right();
+            } else {
+                loc = next;
+            }
+        }
+    }
+
+    /// All locations where a single added obstacle makes the guard loop.
+    ///
+    /// Only locations on the original path can change the walk, and the guard
+    /// is started right before the first time it would reach the obstacle.
+    /// The candidates are checked in parallel and returned sorted.
+    pub fn loop_obstructions(&self, start: Location, dir: Direction) -> Vec<Location> {
+        let patrol = self.patrol(start, dir);
+        let mut first = vec![];
+        let mut seen = HashSet::from([start]);
+        for pair in patrol.path.windows(2) {
+            let (from, next) = (pair[0], pair[1].0);
+            if seen.insert(next) {
+                first.push((next, from));
+            }
+        }
+
+        let mut found: Vec<Location> = first
+            .into_par_iter()
+            .filter(|(obstacle, (loc, dir))| self.loops_with(*loc, *dir, *obstacle))
+            .map(|(obstacle, _)| obstacle)
+            .collect();
+        found.sort();
+        found
+    }
+}
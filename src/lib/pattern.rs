@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use crate::{Distance, Location, VisualMap};
+
+/// One of the eight orientations of a 2D pattern (the dihedral group of the square).
+///
+/// The pattern is first mirrored horizontally (if `flipped`) and then rotated
+/// clockwise by `rotation` quarter turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Orientation {
+    pub rotation: u8,
+    pub flipped: bool,
+}
+
+impl Orientation {
+    /// The orientation that leaves the pattern as it was parsed
+    pub const IDENTITY: Orientation = Orientation {
+        rotation: 0,
+        flipped: false,
+    };
+
+    /// All eight orientations, starting with the identity
+    ///
+    /// ```
+    /// # use aoc24::pattern::Orientation;
+    /// assert_eq!(Orientation::all().count(), 8);
+    /// assert_eq!(Orientation::all().next(), Some(Orientation::IDENTITY));
+    /// ```
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        [false, true]
+            .into_iter()
+            .flat_map(|flipped| (0..4).map(move |rotation| Orientation { rotation, flipped }))
+    }
+
+    /// Transform an offset relative to the pattern anchor
+    ///
+    /// ```
+    /// # use aoc24::pattern::Orientation;
+    /// # use aoc24::Distance;
+    /// let quarter = Orientation { rotation: 1, flipped: false };
+    /// assert_eq!(quarter.transform(&Distance::new(0, 1)), Distance::new(1, 0));
+    /// let mirror = Orientation { rotation: 0, flipped: true };
+    /// assert_eq!(mirror.transform(&Distance::new(2, 1)), Distance::new(2, -1));
+    /// ```
+    pub fn transform(&self, offset: &Distance) -> Distance {
+        let mut d = *offset;
+        if self.flipped {
+            d = Distance::new(d.row, -d.col);
+        }
+        for _ in 0..self.rotation % 4 {
+            d = Distance::new(d.col, -d.row);
+        }
+        d
+    }
+}
+
+/// A small 2D template that can be searched for in a `VisualMap`.
+///
+/// Cells are stored as offsets relative to the upper left corner of the
+/// parsed text block, wildcard cells are not stored at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern<T> {
+    pub cells: BTreeMap<Distance, T>,
+}
+
+impl Pattern<char> {
+    /// Parse a pattern from a text block, treating `wildcard` as "matches anything"
+    ///
+    /// ```
+    /// # use aoc24::pattern::Pattern;
+    /// let pattern = Pattern::parse_with_wildcard("A?\n?B", '?');
+    /// assert_eq!(pattern.cells.len(), 2);
+    /// ```
+    pub fn parse_with_wildcard(text: &str, wildcard: char) -> Self {
+        let cells = text
+            .lines()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(move |(_, c)| *c != wildcard)
+                    .map(move |(col, c)| (Distance::new(row as isize, col as isize), c))
+            })
+            .collect();
+        Pattern { cells }
+    }
+}
+
+impl FromStr for Pattern<char> {
+    type Err = std::convert::Infallible;
+
+    /// Parse a pattern from a text block, treating `.` as wildcard
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Pattern::parse_with_wildcard(s, '.'))
+    }
+}
+
+impl<T: Clone + PartialEq> Pattern<T> {
+    /// Create the pattern as it looks in the given orientation
+    pub fn oriented(&self, orientation: Orientation) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|(offset, v)| (orientation.transform(offset), v.clone()))
+            .collect();
+        Pattern { cells }
+    }
+
+    /// All distinct orientations of the pattern.
+    ///
+    /// Symmetric patterns look the same in several orientations, only the first
+    /// orientation producing a given shape is kept so that matches are not
+    /// counted twice. Two shapes are the same if they are equal up to translation.
+    ///
+    /// ```
+    /// # use aoc24::pattern::Pattern;
+    /// let line: Pattern<char> = "XMAS".parse().unwrap();
+    /// assert_eq!(line.orientations().len(), 4);
+    /// let square: Pattern<char> = "AA\nAA".parse().unwrap();
+    /// assert_eq!(square.orientations().len(), 1);
+    /// ```
+    pub fn orientations(&self) -> Vec<(Orientation, Pattern<T>)> {
+        let mut found: Vec<(Orientation, Pattern<T>)> = Vec::new();
+        for orientation in Orientation::all() {
+            let pattern = self.oriented(orientation);
+            let normalized = pattern.normalized();
+            if !found.iter().any(|(_, p)| p.normalized() == normalized) {
+                found.push((orientation, pattern));
+            }
+        }
+        found
+    }
+
+    /// The pattern moved so that its smallest offset is (0, 0)
+    fn normalized(&self) -> Vec<(Distance, T)> {
+        let min_row = self.cells.keys().map(|d| d.row).min().unwrap_or(0);
+        let min_col = self.cells.keys().map(|d| d.col).min().unwrap_or(0);
+        let mut cells = self
+            .cells
+            .iter()
+            .map(|(d, v)| (Distance::new(d.row - min_row, d.col - min_col), v.clone()))
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|(d, _)| *d);
+        cells
+    }
+
+    /// Check if the pattern matches when its anchor is placed at `anchor`
+    pub fn matches_at(&self, map: &BTreeMap<Location, T>, anchor: &Location) -> bool {
+        self.cells
+            .iter()
+            .all(|(offset, v)| map.get(&anchor.apply_distance(offset)) == Some(v))
+    }
+}
+
+impl<T: Clone + PartialEq> VisualMap<T> {
+    /// Find all anchors and orientations where the pattern matches, sorted by anchor.
+    ///
+    /// The anchor is the location of the upper left cell of the parsed pattern
+    /// text, after the orientation has been applied.
+    ///
+    /// ```
+    /// # use aoc24::pattern::Pattern;
+    /// let input = "\
+    /// MMMSXXMASM
+    /// MSAMXMSMSA
+    /// AMXSXMAAMM
+    /// MSAMASMSMX
+    /// XMASAMXAMM
+    /// XXAMMXXAMA
+    /// SMSMSASXSS
+    /// SAXAMASAAA
+    /// MAMMMXMMMM
+    /// MXMXAXMASX";
+    /// let map = aoc24::read_visual_map(input.lines().map(String::from));
+    /// let x_mas: Pattern<char> = "M.S\n.A.\nM.S".parse().unwrap();
+    /// assert_eq!(map.find_pattern(&x_mas).len(), 9);
+    /// let xmas: Pattern<char> = "XMAS".parse().unwrap();
+    /// let xmas_diagonal: Pattern<char> = "X...\n.M..\n..A.\n...S".parse().unwrap();
+    /// assert_eq!(map.find_pattern(&xmas).len() + map.find_pattern(&xmas_diagonal).len(), 18);
+    /// ```
+    ///
+    /// Anchors do not need to be on the map, so wildcards also match locations
+    /// that were filtered out when reading the map.
+    ///
+    /// ```
+    /// # use aoc24::pattern::{Orientation, Pattern};
+    /// # use aoc24::Location;
+    /// let map = aoc24::read_visual_map_filter_map(
+    ///     ["#A", "B#"].into_iter().map(String::from),
+    ///     |c| (c != '#').then_some(c),
+    /// );
+    /// let pattern: Pattern<char> = ".A\nB.".parse().unwrap();
+    /// assert_eq!(map.find_pattern(&pattern), vec![(Location::new(0, 0), Orientation::IDENTITY)]);
+    /// ```
+    pub fn find_pattern(&self, pattern: &Pattern<T>) -> Vec<(Location, Orientation)> {
+        let orientations = anchored_orientations(pattern);
+        let mut found: Vec<_> = self
+            .map
+            .keys()
+            .flat_map(|loc| matches_from(&self.map, &orientations, loc))
+            .collect();
+        found.sort();
+        found
+    }
+}
+
+/// The distinct orientations of the pattern, each with the offset of one of its
+/// concrete cells.
+///
+/// Every match covers a map location with that cell, so anchors are found by
+/// going back from the map locations by that offset, which also works when the
+/// pattern's own anchor cell is a wildcard on a map with missing locations.
+fn anchored_orientations<T: Clone + PartialEq>(
+    pattern: &Pattern<T>,
+) -> Vec<(Orientation, Pattern<T>, Distance)> {
+    pattern
+        .orientations()
+        .into_iter()
+        .filter_map(|(orientation, p)| {
+            let offset = *p.cells.keys().next()?;
+            Some((orientation, p, offset))
+        })
+        .collect()
+}
+
+/// All matches that have the reference cell of their orientation at `loc`
+fn matches_from<'a, T: Clone + PartialEq>(
+    map: &'a BTreeMap<Location, T>,
+    orientations: &'a [(Orientation, Pattern<T>, Distance)],
+    loc: &'a Location,
+) -> impl Iterator<Item = (Location, Orientation)> + 'a {
+    orientations
+        .iter()
+        .filter_map(move |(orientation, p, offset)| {
+            let anchor = Location::new(loc.row - offset.row, loc.col - offset.col);
+            p.matches_at(map, &anchor).then_some((anchor, *orientation))
+        })
+}
+
+impl<T: Clone + PartialEq + Send + Sync> VisualMap<T> {
+    /// Like `find_pattern`, but checks the anchors in parallel
+    ///
+    /// ```
+    /// # use aoc24::pattern::Pattern;
+    /// let map = aoc24::read_visual_map("ABA\nBAB".lines().map(String::from));
+    /// let pattern: Pattern<char> = "AB".parse().unwrap();
+    /// assert_eq!(map.find_pattern_par(&pattern), map.find_pattern(&pattern));
+    /// ```
+    pub fn find_pattern_par(&self, pattern: &Pattern<T>) -> Vec<(Location, Orientation)> {
+        let orientations = anchored_orientations(pattern);
+        let mut found: Vec<_> = self
+            .map
+            .par_iter()
+            .flat_map_iter(|(loc, _)| matches_from(&self.map, &orientations, loc))
+            .collect();
+        found.sort();
+        found
+    }
+}
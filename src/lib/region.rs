@@ -0,0 +1,488 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::iter::once;
+
+use crate::{Direction, Location, VisualMap};
+
+/// A closed, axis-aligned polygon given by its vertices in traversal order.
+///
+/// When the polygon is the boundary of a set of cells, vertices are cell
+/// corners: corner (row, col) is the upper left corner of cell (row, col).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<Location>,
+}
+
+impl Polygon {
+    /// Build a polygon by walking from `start`, as given by dig-plan style inputs.
+    ///
+    /// The walk is expected to end at `start` again.
+    ///
+    /// ```
+    /// # use aoc24::region::Polygon;
+    /// # use aoc24::{Direction, Location};
+    /// use Direction::*;
+    /// let plan = [
+    ///     (Right, 6), (Down, 5), (Left, 2), (Down, 2), (Right, 2), (Down, 2), (Left, 5),
+    ///     (Up, 2), (Left, 1), (Up, 2), (Right, 2), (Up, 3), (Left, 2), (Up, 2),
+    /// ];
+    /// let polygon = Polygon::from_steps(Location::new(0, 0), plan);
+    /// assert_eq!(polygon.sides(), 14);
+    /// assert_eq!(polygon.perimeter(), 38);
+    /// assert_eq!(polygon.area(), 42);
+    /// assert_eq!(polygon.interior_points() + polygon.perimeter(), 62);
+    /// ```
+    pub fn from_steps(
+        start: Location,
+        steps: impl IntoIterator<Item = (Direction, usize)>,
+    ) -> Self {
+        let mut vertices = vec![start];
+        let mut current = start;
+        for (dir, n) in steps {
+            current = current.apply_n(dir, n);
+            vertices.push(current);
+        }
+        if vertices.len() > 1 && vertices.last() == vertices.first() {
+            vertices.pop();
+        }
+        Polygon { vertices }.simplified()
+    }
+
+    /// Remove vertices that lie on a straight line between their neighbors
+    fn simplified(mut self) -> Self {
+        loop {
+            let n = self.vertices.len();
+            if n < 3 {
+                return self;
+            }
+            let collinear = (0..n).find(|&i| {
+                let prev = self.vertices[(i + n - 1) % n];
+                let cur = self.vertices[i];
+                let next = self.vertices[(i + 1) % n];
+                (prev.row == cur.row && cur.row == next.row)
+                    || (prev.col == cur.col && cur.col == next.col)
+            });
+            match collinear {
+                Some(i) => {
+                    self.vertices.remove(i);
+                }
+                None => return self,
+            }
+        }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Location, &Location)> {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    /// Number of straight sides of the polygon
+    #[inline(always)]
+    pub fn sides(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Length of the boundary, counting every lattice step
+    pub fn perimeter(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| a.row.abs_diff(b.row) + a.col.abs_diff(b.col))
+            .sum()
+    }
+
+    /// Twice the signed area using the shoelace formula.
+    ///
+    /// The sign is positive for clockwise traversal as seen on screen
+    /// (rows growing downwards), negative otherwise.
+    pub fn signed_area2(&self) -> isize {
+        self.edges()
+            .map(|(a, b)| a.col * b.row - b.col * a.row)
+            .sum()
+    }
+
+    /// Enclosed area using the shoelace formula
+    pub fn area(&self) -> usize {
+        self.signed_area2().unsigned_abs() / 2
+    }
+
+    /// Number of lattice points strictly inside the polygon, by Pick's theorem
+    pub fn interior_points(&self) -> usize {
+        self.area() + 1 - self.perimeter() / 2
+    }
+
+    /// Check if the polygon is traversed clockwise on screen
+    pub fn is_clockwise(&self) -> bool {
+        self.signed_area2() > 0
+    }
+}
+
+/// The boundary polygons of a set of cells.
+///
+/// Outer polygons are traversed clockwise and inner polygons (around holes)
+/// counterclockwise, so the cells are always on the right hand side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boundary {
+    pub outer: Vec<Polygon>,
+    pub inner: Vec<Polygon>,
+}
+
+impl Boundary {
+    /// Total number of straight sides, including the ones around holes
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use aoc24::region::trace_boundary;
+    /// let map = aoc24::read_visual_map(
+    ///     "AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA".lines().map(String::from),
+    /// );
+    /// let a: HashSet<_> = map.map.iter().filter(|(_, c)| **c == 'A').map(|(l, _)| *l).collect();
+    /// let boundary = trace_boundary(&a);
+    /// assert_eq!(boundary.sides(), 12);
+    /// assert_eq!(boundary.area(), 28);
+    /// ```
+    pub fn sides(&self) -> usize {
+        self.polygons().map(Polygon::sides).sum()
+    }
+
+    /// Total boundary length, including the boundary around holes
+    pub fn perimeter(&self) -> usize {
+        self.polygons().map(Polygon::perimeter).sum()
+    }
+
+    /// Enclosed area without the holes
+    pub fn area(&self) -> usize {
+        self.outer.iter().map(Polygon::area).sum::<usize>()
+            - self.inner.iter().map(Polygon::area).sum::<usize>()
+    }
+
+    pub fn polygons(&self) -> impl Iterator<Item = &Polygon> {
+        self.outer.iter().chain(self.inner.iter())
+    }
+}
+
+/// Trace the ordered boundary polygons of a set of cells.
+///
+/// Cells only touching at a corner are treated as not connected, so each
+/// outer polygon hugs 4-connected cells. Holes touching at a corner share
+/// one inner polygon.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use aoc24::region::trace_boundary;
+/// # use aoc24::Location;
+/// // AAA
+/// // A.A
+/// // AAA
+/// let cells: HashSet<Location> = (0..3)
+///     .flat_map(|row| (0..3).map(move |col| Location::new(row, col)))
+///     .filter(|loc| *loc != Location::new(1, 1))
+///     .collect();
+/// let boundary = trace_boundary(&cells);
+/// assert_eq!(boundary.outer.len(), 1);
+/// assert_eq!(boundary.inner.len(), 1);
+/// assert_eq!(boundary.sides(), 8);
+/// assert_eq!(boundary.perimeter(), 16);
+/// assert_eq!(boundary.area(), 8);
+/// assert_eq!(boundary.outer[0].vertices[0], Location::new(0, 0));
+/// ```
+pub fn trace_boundary(cells: &HashSet<Location>) -> Boundary {
+    // directed unit edges between corners, keeping the cells on the right
+    let mut edges: HashMap<Location, Vec<Direction>> = HashMap::new();
+    for cell in cells {
+        let corner = *cell;
+        if !cells.contains(&cell.up()) {
+            edges.entry(corner).or_default().push(Direction::Right);
+        }
+        if !cells.contains(&cell.right()) {
+            edges
+                .entry(corner.right())
+                .or_default()
+                .push(Direction::Down);
+        }
+        if !cells.contains(&cell.down()) {
+            edges
+                .entry(corner.down().right())
+                .or_default()
+                .push(Direction::Left);
+        }
+        if !cells.contains(&cell.left()) {
+            edges.entry(corner.down()).or_default().push(Direction::Up);
+        }
+    }
+
+    let mut outer = Vec::new();
+    let mut inner = Vec::new();
+    // the smallest remaining corner is never a pinch point, so the loop
+    // started there is complete once we are back at it
+    while let Some(&start) = edges.keys().min() {
+        let mut dir = take_edge(&mut edges, &start, &[]).unwrap();
+        let mut corner = start;
+        let mut vertices = Vec::new();
+        loop {
+            vertices.push(corner);
+            corner = corner.apply(dir);
+            if corner == start {
+                break;
+            }
+            let preference = [dir.turn_right(), dir, dir.turn_left()];
+            dir = take_edge(&mut edges, &corner, &preference)
+                .expect("boundary corner without outgoing edge");
+        }
+        let polygon = Polygon { vertices }.simplified();
+        if polygon.is_clockwise() {
+            outer.push(polygon);
+        } else {
+            inner.push(polygon);
+        }
+    }
+    Boundary { outer, inner }
+}
+
+/// Remove and return the first preferred outgoing edge at `corner`
+fn take_edge(
+    edges: &mut HashMap<Location, Vec<Direction>>,
+    corner: &Location,
+    preference: &[Direction],
+) -> Option<Direction> {
+    let outgoing = edges.get_mut(corner)?;
+    let idx = preference
+        .iter()
+        .find_map(|d| outgoing.iter().position(|o| o == d))
+        .unwrap_or(0);
+    if idx >= outgoing.len() {
+        return None;
+    }
+    let dir = outgoing.swap_remove(idx);
+    if outgoing.is_empty() {
+        edges.remove(corner);
+    }
+    Some(dir)
+}
+
+/// A 4-connected set of cells sharing the same value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<T> {
+    pub value: T,
+    pub cells: HashSet<Location>,
+}
+
+impl<T> Region<T> {
+    #[inline(always)]
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Trace the boundary polygons of the region
+    pub fn boundary(&self) -> Boundary {
+        trace_boundary(&self.cells)
+    }
+}
+
+/// The connected regions of a `VisualMap`, together with their containment hierarchy.
+///
+/// A hole of a region is a 4-connected set of cells outside the region that
+/// can not reach the outside of the region's bounding box without crossing
+/// the region. A region is enclosed by another one if it lies in one of its holes.
+#[derive(Debug, Clone)]
+pub struct Regions<T> {
+    pub regions: Vec<Region<T>>,
+    pub ids: BTreeMap<Location, usize>,
+    holes: Vec<Vec<HashSet<Location>>>,
+    outer: Vec<Option<usize>>,
+}
+
+impl<T> Regions<T> {
+    /// Label regions from already known connected cell sets.
+    ///
+    /// The region ids are the indices into `regions`.
+    pub fn from_regions(regions: Vec<Region<T>>) -> Self {
+        let ids: BTreeMap<Location, usize> = regions
+            .iter()
+            .enumerate()
+            .flat_map(|(id, region)| region.cells.iter().map(move |loc| (*loc, id)))
+            .collect();
+        let holes: Vec<Vec<HashSet<Location>>> = regions
+            .iter()
+            .map(|region| find_holes(&region.cells))
+            .collect();
+
+        // the innermost enclosing region is the one with the smallest hole
+        let mut outer: Vec<Option<usize>> = vec![None; regions.len()];
+        let mut outer_hole_size: Vec<usize> = vec![usize::MAX; regions.len()];
+        for (id, region_holes) in holes.iter().enumerate() {
+            for hole in region_holes {
+                let enclosed: HashSet<usize> = hole
+                    .iter()
+                    .filter_map(|loc| ids.get(loc))
+                    .copied()
+                    .collect();
+                for inner in enclosed {
+                    if hole.len() < outer_hole_size[inner] {
+                        outer[inner] = Some(id);
+                        outer_hole_size[inner] = hole.len();
+                    }
+                }
+            }
+        }
+
+        Regions {
+            regions,
+            ids,
+            holes,
+            outer,
+        }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn get(&self, id: usize) -> &Region<T> {
+        &self.regions[id]
+    }
+
+    /// The id of the region at the given location
+    #[inline(always)]
+    pub fn id_at(&self, loc: &Location) -> Option<usize> {
+        self.ids.get(loc).copied()
+    }
+
+    /// The innermost region that completely encloses the region `id`
+    #[inline(always)]
+    pub fn outer_region(&self, id: usize) -> Option<usize> {
+        self.outer[id]
+    }
+
+    /// The holes of region `id`, each as the set of cells it covers
+    #[inline(always)]
+    pub fn holes(&self, id: usize) -> &[HashSet<Location>] {
+        &self.holes[id]
+    }
+
+    /// The regions directly enclosed by region `id`, i.e. one level down in the hierarchy
+    pub fn enclosed_regions(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outer
+            .iter()
+            .enumerate()
+            .filter(move |(_, outer)| **outer == Some(id))
+            .map(|(inner, _)| inner)
+    }
+
+    /// The regions not enclosed by any other region
+    pub fn top_level_regions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.outer
+            .iter()
+            .enumerate()
+            .filter(|(_, outer)| outer.is_none())
+            .map(|(id, _)| id)
+    }
+
+    /// Number of regions enclosing region `id`
+    pub fn depth(&self, id: usize) -> usize {
+        std::iter::successors(self.outer[id], |&o| self.outer[o]).count()
+    }
+}
+
+impl<T: Clone + PartialEq> VisualMap<T> {
+    /// Split the map into 4-connected regions of equal values.
+    ///
+    /// ```
+    /// let map = aoc24::read_visual_map(
+    ///     "AAAAA\nABBBA\nABCBA\nABBBA\nAAAAA\n".lines().map(String::from),
+    /// );
+    /// let regions = map.regions();
+    /// assert_eq!(regions.len(), 3);
+    /// let a = regions.id_at(&aoc24::Location::new(0, 0)).unwrap();
+    /// let b = regions.id_at(&aoc24::Location::new(1, 1)).unwrap();
+    /// let c = regions.id_at(&aoc24::Location::new(2, 2)).unwrap();
+    /// assert_eq!(regions.outer_region(a), None);
+    /// assert_eq!(regions.outer_region(b), Some(a));
+    /// assert_eq!(regions.outer_region(c), Some(b));
+    /// assert_eq!(regions.depth(c), 2);
+    /// assert_eq!(regions.holes(a).len(), 1);
+    /// assert_eq!(regions.holes(a)[0].len(), 9);
+    /// assert_eq!(regions.enclosed_regions(a).collect::<Vec<_>>(), vec![b]);
+    /// ```
+    ///
+    /// Regions touching only at a corner are not connected, and a corner gap
+    /// is no way out of an enclosing region:
+    ///
+    /// ```
+    /// let map = aoc24::read_visual_map(
+    ///     "OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO".lines().map(String::from),
+    /// );
+    /// let regions = map.regions();
+    /// assert_eq!(regions.len(), 5);
+    /// let o = regions.id_at(&aoc24::Location::new(0, 0)).unwrap();
+    /// assert_eq!(regions.holes(o).len(), 4);
+    /// assert_eq!(regions.enclosed_regions(o).count(), 4);
+    /// ```
+    pub fn regions(&self) -> Regions<T> {
+        // components come in order of their first location, so ids are stable
+        let regions = self
+            .union_find()
+            .components()
+            .into_iter()
+            .map(|cells| Region {
+                value: self.map.get(&cells[0]).unwrap().clone(),
+                cells: cells.into_iter().collect(),
+            })
+            .collect();
+        Regions::from_regions(regions)
+    }
+}
+
+/// Find the 4-connected components of the complement of `cells` that are
+/// fully surrounded by `cells`
+fn find_holes(cells: &HashSet<Location>) -> Vec<HashSet<Location>> {
+    let Some(first) = cells.iter().next() else {
+        return Vec::new();
+    };
+    let (mut upper_left, mut lower_right) = (*first, *first);
+    for loc in cells {
+        upper_left = Location::new(upper_left.row.min(loc.row), upper_left.col.min(loc.col));
+        lower_right = Location::new(lower_right.row.max(loc.row), lower_right.col.max(loc.col));
+    }
+    // pad the bounding box so that the outside is connected
+    let upper_left = upper_left.up().left();
+    let lower_right = lower_right.down().right();
+
+    let mut outside: HashSet<Location> = once(upper_left).collect();
+    let mut stack = vec![upper_left];
+    while let Some(l) = stack.pop() {
+        for l_test in [l.up(), l.down(), l.left(), l.right()] {
+            if l_test.is_inside_bounding_box(&upper_left, &lower_right)
+                && !cells.contains(&l_test)
+                && outside.insert(l_test)
+            {
+                stack.push(l_test);
+            }
+        }
+    }
+
+    let mut rest: BTreeSet<Location> = (upper_left.row..=lower_right.row)
+        .flat_map(|row| (upper_left.col..=lower_right.col).map(move |col| Location::new(row, col)))
+        .filter(|loc| !cells.contains(loc) && !outside.contains(loc))
+        .collect();
+    let mut holes = Vec::new();
+    while let Some(loc) = rest.pop_first() {
+        let mut stack = vec![loc];
+        let mut hole: HashSet<Location> = once(loc).collect();
+        while let Some(l) = stack.pop() {
+            for l_test in [l.up(), l.down(), l.left(), l.right()] {
+                if rest.remove(&l_test) {
+                    hole.insert(l_test);
+                    stack.push(l_test);
+                }
+            }
+        }
+        holes.push(hole);
+    }
+    holes
+}
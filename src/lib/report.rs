@@ -0,0 +1,121 @@
+use std::ops::RangeInclusive;
+
+/// Checks that a report of levels is strictly monotone with bounded steps,
+/// tolerating up to `tolerance` removed levels.
+///
+/// ```
+/// # use aoc24::report::ReportChecker;
+/// let reports = [
+///     vec![7, 6, 4, 2, 1],
+///     vec![1, 2, 7, 8, 9],
+///     vec![9, 7, 6, 2, 1],
+///     vec![1, 3, 2, 4, 5],
+///     vec![8, 6, 4, 4, 1],
+///     vec![1, 3, 6, 7, 9],
+/// ];
+/// let strict = ReportChecker::default();
+/// let dampened = ReportChecker::default().with_tolerance(1);
+/// assert_eq!(reports.iter().filter(|r| strict.is_safe(r)).count(), 2);
+/// assert_eq!(reports.iter().filter(|r| dampened.is_safe(r)).count(), 4);
+/// assert_eq!(dampened.check(&reports[3]), Some(vec![1]));
+/// assert_eq!(dampened.check(&reports[1]), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportChecker {
+    /// Allowed absolute difference between neighboring levels
+    pub steps: RangeInclusive<i64>,
+    /// Number of levels that may be removed
+    pub tolerance: usize,
+}
+
+impl Default for ReportChecker {
+    fn default() -> Self {
+        ReportChecker::new(1..=3)
+    }
+}
+
+impl ReportChecker {
+    pub fn new(steps: RangeInclusive<i64>) -> Self {
+        ReportChecker {
+            steps,
+            tolerance: 0,
+        }
+    }
+
+    pub fn with_tolerance(self, tolerance: usize) -> Self {
+        ReportChecker { tolerance, ..self }
+    }
+
+    #[inline(always)]
+    pub fn is_safe(&self, report: &[i64]) -> bool {
+        self.check(report).is_some()
+    }
+
+    /// Find the fewest levels to remove to make the report safe.
+    ///
+    /// Returns the indices to remove in ascending order, or `None` if more than
+    /// `tolerance` removals would be needed. Empty and single level reports
+    /// are always safe. Runs in O(n * tolerance).
+    ///
+    /// ```
+    /// # use aoc24::report::ReportChecker;
+    /// let checker = ReportChecker::default().with_tolerance(2);
+    /// assert_eq!(checker.check(&[]), Some(vec![]));
+    /// assert_eq!(checker.check(&[5]), Some(vec![]));
+    /// assert_eq!(checker.check(&[1, 9, 2, 3, 8, 4]), Some(vec![1, 4]));
+    /// assert_eq!(checker.check(&[9, 1, 2, 3]), Some(vec![0]));
+    /// assert_eq!(checker.check(&[1, 2, 3, 9, 9, 9]), None);
+    /// ```
+    pub fn check(&self, report: &[i64]) -> Option<Vec<usize>> {
+        [true, false]
+            .into_iter()
+            .filter_map(|ascending| self.check_direction(report, ascending))
+            .min_by_key(|removed| removed.len())
+    }
+
+    fn check_direction(&self, report: &[i64], ascending: bool) -> Option<Vec<usize>> {
+        let n = report.len();
+        let k = self.tolerance;
+        let valid_step = |from: usize, to: usize| {
+            let delta = report[to] - report[from];
+            self.steps.contains(&if ascending { delta } else { -delta })
+        };
+
+        // fewest removals among report[..=i] if report[i] is kept, and the
+        // previously kept index for that choice, preferring to remove early levels
+        let mut removals: Vec<Option<usize>> = vec![None; n];
+        let mut previous: Vec<Option<usize>> = vec![None; n];
+        for i in 0..n {
+            if i <= k {
+                removals[i] = Some(i);
+            }
+            for p in i.saturating_sub(k + 1)..i {
+                let Some(r) = removals[p] else {
+                    continue;
+                };
+                let r = r + (i - p - 1);
+                if r <= k && valid_step(p, i) && removals[i].is_none_or(|best| r <= best) {
+                    removals[i] = Some(r);
+                    previous[i] = Some(p);
+                }
+            }
+        }
+
+        // the last kept level, also removing all levels after it
+        let last = (n.saturating_sub(k + 1)..n)
+            .filter_map(|i| Some((i, removals[i]? + (n - 1 - i))))
+            .filter(|(_, r)| *r <= k)
+            .min_by_key(|(_, r)| *r);
+        let Some((last, _)) = last else {
+            return (n == 0).then(Vec::new);
+        };
+
+        let mut kept = vec![false; n];
+        let mut cur = Some(last);
+        while let Some(i) = cur {
+            kept[i] = true;
+            cur = previous[i];
+        }
+        Some((0..n).filter(|&i| !kept[i]).collect())
+    }
+}
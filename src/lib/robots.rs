@@ -0,0 +1,187 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+
+use crate::math::wrap_location;
+use crate::{Distance, Location, VisualMap};
+
+/// A robot moving with constant velocity.
+///
+/// Parsed from `p=x,y v=dx,dy`, where `x` is the column and `y` the row.
+///
+/// ```
+/// # use aoc24::robots::Robot;
+/// # use aoc24::{Distance, Location};
+/// let robot: Robot = "p=2,4 v=2,-3".parse().unwrap();
+/// assert_eq!(robot.position, Location::new(4, 2));
+/// assert_eq!(robot.velocity, Distance::new(-3, 2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Robot {
+    pub position: Location,
+    pub velocity: Distance,
+}
+
+/// Parse `x,y` into `(row, col)`
+fn parse_xy(s: &str) -> anyhow::Result<(isize, isize)> {
+    let (x, y) = s.split_once(',').context("missing ','")?;
+    Ok((y.trim().parse()?, x.trim().parse()?))
+}
+
+impl FromStr for Robot {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (p, v) = s.trim().split_once(' ').context("missing ' '")?;
+        let (row, col) = parse_xy(p.strip_prefix("p=").context("missing 'p='")?)?;
+        let (drow, dcol) = parse_xy(v.strip_prefix("v=").context("missing 'v='")?)?;
+        Ok(Robot {
+            position: Location::new(row, col),
+            velocity: Distance::new(drow, dcol),
+        })
+    }
+}
+
+/// A map of the given size wrapping around at all edges.
+///
+/// ```
+/// # use aoc24::robots::{Robot, Torus};
+/// let robots: Vec<Robot> = "\
+/// p=0,4 v=3,-3\np=6,3 v=-1,-3\np=10,3 v=-1,2\np=2,0 v=2,-1\np=0,0 v=1,3\np=3,0 v=-2,-2
+/// p=7,6 v=-1,-3\np=3,0 v=-1,-2\np=9,3 v=2,3\np=7,3 v=-1,2\np=2,4 v=2,-3\np=9,5 v=-3,-3"
+///     .lines()
+///     .map(|line| line.parse().unwrap())
+///     .collect();
+/// let torus = Torus::new(7, 11);
+/// let positions = torus.positions(&robots, 100);
+/// assert_eq!(torus.quadrants(&positions), [1, 3, 4, 1]);
+/// assert_eq!(torus.safety_factor(&positions), 12);
+/// assert_eq!(
+///     torus.render(&positions),
+///     "......2..1.\n...........\n1..........\n.11........\n.....1.....\n...12......\n.1....1....\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Torus {
+    pub height: isize,
+    pub width: isize,
+}
+
+impl Torus {
+    pub fn new(height: isize, width: isize) -> Self {
+        Torus { height, width }
+    }
+
+    /// Where `robot` is after `steps` steps, computed directly
+    pub fn position(&self, robot: &Robot, steps: isize) -> Location {
+        let height_steps = steps.rem_euclid(self.height);
+        let width_steps = steps.rem_euclid(self.width);
+        let row = robot.position.row + robot.velocity.row * height_steps;
+        let col = robot.position.col + robot.velocity.col * width_steps;
+        wrap_location(&Location::new(row, col), self.height, self.width)
+    }
+
+    /// Where all robots are after `steps` steps
+    pub fn positions(&self, robots: &[Robot], steps: isize) -> Vec<Location> {
+        robots.iter().map(|r| self.position(r, steps)).collect()
+    }
+
+    /// Number of positions in the upper left, upper right, lower left and
+    /// lower right quadrant, ignoring the middle row and column
+    pub fn quadrants(&self, positions: &[Location]) -> [usize; 4] {
+        let (mid_row, mid_col) = (self.height / 2, self.width / 2);
+        let mut counts = [0; 4];
+        for loc in positions {
+            // with an odd size the middle belongs to no quadrant
+            let upper = loc.row < mid_row;
+            let lower = loc.row >= self.height - mid_row;
+            let left = loc.col < mid_col;
+            let right = loc.col >= self.width - mid_col;
+            match (upper, lower, left, right) {
+                (true, _, true, _) => counts[0] += 1,
+                (true, _, _, true) => counts[1] += 1,
+                (_, true, true, _) => counts[2] += 1,
+                (_, true, _, true) => counts[3] += 1,
+                _ => {}
+            }
+        }
+        counts
+    }
+
+    /// The product of the quadrant counts
+    pub fn safety_factor(&self, positions: &[Location]) -> usize {
+        self.quadrants(positions).iter().product()
+    }
+
+    /// Fraction of positions with another robot on a neighboring location,
+    /// close to 1 when the robots form a picture
+    pub fn clustering(&self, positions: &[Location]) -> f64 {
+        if positions.is_empty() {
+            return 0.0;
+        }
+        let map = self.count_map(positions);
+        let clustered = positions
+            .iter()
+            .filter(|loc| {
+                [loc.up(), loc.down(), loc.left(), loc.right()]
+                    .iter()
+                    .any(|n| {
+                        map.map
+                            .contains_key(&wrap_location(n, self.height, self.width))
+                    })
+            })
+            .count();
+        clustered as f64 / positions.len() as f64
+    }
+
+    /// Shannon entropy in bits of the distribution of positions over
+    /// `block` x `block` tiles, low when the robots form a picture
+    pub fn entropy(&self, positions: &[Location], block: isize) -> f64 {
+        let tiles = self.count_map(
+            &positions
+                .iter()
+                .map(|loc| Location::new(loc.row / block, loc.col / block))
+                .collect::<Vec<_>>(),
+        );
+        let total = positions.len() as f64;
+        tiles
+            .map
+            .values()
+            .map(|count| {
+                let p = *count as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// The step in `0..height * width` with the lowest entropy.
+    ///
+    /// The positions repeat after `lcm(height, width)` steps, which is
+    /// `height * width` for the usual prime sizes.
+    pub fn lowest_entropy_step(&self, robots: &[Robot], block: isize) -> isize {
+        (0..self.height * self.width)
+            .map(|step| (self.entropy(&self.positions(robots, step), block), step))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(0, |(_, step)| step)
+    }
+
+    /// Number of robots per location
+    pub fn count_map(&self, positions: &[Location]) -> VisualMap<usize> {
+        let mut map = VisualMap {
+            map: Default::default(),
+            max: Location::new(self.height - 1, self.width - 1),
+        };
+        for loc in positions {
+            *map.map.entry(*loc).or_insert(0) += 1;
+        }
+        map
+    }
+
+    /// Draw the number of robots per location, or `.` for none
+    pub fn render(&self, positions: &[Location]) -> String {
+        self.count_map(positions).render(|_, count| match count {
+            Some(n) => char::from_digit((*n).min(9) as u32, 10).unwrap(),
+            None => '.',
+        })
+    }
+}
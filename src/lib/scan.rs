@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// The shape of an instruction like `mul(123,4)`: a name followed by a
+/// parenthesized, comma separated list of decimal arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionShape {
+    pub name: &'static str,
+    pub args: usize,
+    /// Allowed number of digits of each argument
+    pub digits: RangeInclusive<usize>,
+}
+
+impl InstructionShape {
+    pub fn new(name: &'static str, args: usize, digits: RangeInclusive<usize>) -> Self {
+        InstructionShape { name, args, digits }
+    }
+
+    /// Match the instruction at the start of `input`, returning the arguments
+    /// and the matched length
+    fn match_at(&self, input: &[u8]) -> Option<(Vec<u64>, usize)> {
+        let mut pos = self.name.len();
+        if !input.starts_with(self.name.as_bytes()) || input.get(pos) != Some(&b'(') {
+            return None;
+        }
+        pos += 1;
+        let mut args = Vec::with_capacity(self.args);
+        for i in 0..self.args {
+            if i > 0 {
+                if input.get(pos) != Some(&b',') {
+                    return None;
+                }
+                pos += 1;
+            }
+            let len = input[pos..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            if !self.digits.contains(&len) {
+                return None;
+            }
+            let arg = input[pos..pos + len].iter().try_fold(0_u64, |acc, b| {
+                acc.checked_mul(10)?.checked_add((b - b'0') as u64)
+            })?;
+            args.push(arg);
+            pos += len;
+        }
+        (input.get(pos) == Some(&b')')).then_some((args, pos + 1))
+    }
+}
+
+/// An instruction found in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub name: &'static str,
+    pub args: Vec<u64>,
+    /// Byte offset of the first byte of the instruction
+    pub offset: usize,
+    /// Number of bytes of the instruction
+    pub len: usize,
+}
+
+/// Finds instructions in corrupted input, skipping everything else.
+///
+/// Like a regex alternation, the first shape matching at the leftmost
+/// position wins and matches never overlap.
+///
+/// ```
+/// # use aoc24::scan::{InstructionShape, Scanner};
+/// let scanner = Scanner::new(vec![InstructionShape::new("mul", 2, 1..=3)]);
+/// let input = b"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+/// let tokens: Vec<_> = scanner.scan(input).collect();
+/// assert_eq!(tokens.len(), 4);
+/// assert_eq!((tokens[0].offset, tokens[0].len), (1, 8));
+/// assert_eq!(tokens[1].args, vec![5, 5]);
+/// assert_eq!(tokens.iter().map(|t| t.args[0] * t.args[1]).sum::<u64>(), 161);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    shapes: Vec<InstructionShape>,
+}
+
+impl Scanner {
+    pub fn new(shapes: Vec<InstructionShape>) -> Self {
+        Scanner { shapes }
+    }
+
+    /// Lazily yield all instructions in `input`
+    pub fn scan<'a>(&'a self, input: &'a [u8]) -> impl Iterator<Item = Token> + 'a {
+        let mut pos = 0;
+        std::iter::from_fn(move || {
+            while pos < input.len() {
+                let found = self.shapes.iter().find_map(|shape| {
+                    shape.match_at(&input[pos..]).map(|(args, len)| Token {
+                        name: shape.name,
+                        args,
+                        offset: pos,
+                        len,
+                    })
+                });
+                match found {
+                    Some(token) => {
+                        pos += token.len;
+                        return Some(token);
+                    }
+                    None => pos += 1,
+                }
+            }
+            None
+        })
+    }
+}
+
+/// The machine state the instructions work on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    pub enabled: bool,
+    pub total: u64,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            enabled: true,
+            total: 0,
+        }
+    }
+}
+
+/// Executes an instruction, returns `false` if it was skipped
+pub type Handler = fn(&mut State, &[u64]) -> bool;
+
+/// Handler enabling the following instructions
+pub fn enable(state: &mut State, _args: &[u64]) -> bool {
+    state.enabled = true;
+    true
+}
+
+/// Handler disabling the following instructions
+pub fn disable(state: &mut State, _args: &[u64]) -> bool {
+    state.enabled = false;
+    true
+}
+
+/// Handler adding the product of the arguments to the total, if enabled
+pub fn mul(state: &mut State, args: &[u64]) -> bool {
+    if state.enabled {
+        state.total += args.iter().product::<u64>();
+    }
+    state.enabled
+}
+
+/// The result of interpreting some input
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub state: State,
+    /// Every instruction found and if it was executed
+    pub trace: Vec<(Token, bool)>,
+}
+
+impl Run {
+    /// The instructions that were found but not executed
+    pub fn skipped(&self) -> impl Iterator<Item = &Token> {
+        self.trace
+            .iter()
+            .filter(|(_, executed)| !executed)
+            .map(|(token, _)| token)
+    }
+}
+
+/// Runs the instructions found in corrupted input.
+///
+/// ```
+/// # use aoc24::scan::{self, InstructionShape, Interpreter};
+/// let input = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+/// let run = Interpreter::new()
+///     .instruction(InstructionShape::new("mul", 2, 1..=3), scan::mul)
+///     .instruction(InstructionShape::new("do", 0, 0..=0), scan::enable)
+///     .instruction(InstructionShape::new("don't", 0, 0..=0), scan::disable)
+///     .run(input);
+/// assert_eq!(run.state.total, 48);
+/// let skipped: Vec<_> = run.skipped().map(|t| t.offset).collect();
+/// assert_eq!(skipped, vec![28, 48]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interpreter {
+    shapes: Vec<InstructionShape>,
+    handlers: HashMap<&'static str, Handler>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an instruction, shapes added first take precedence
+    pub fn instruction(mut self, shape: InstructionShape, handler: Handler) -> Self {
+        self.handlers.insert(shape.name, handler);
+        self.shapes.push(shape);
+        self
+    }
+
+    pub fn run(&self, input: &[u8]) -> Run {
+        let scanner = Scanner::new(self.shapes.clone());
+        let mut state = State::default();
+        let trace = scanner
+            .scan(input)
+            .map(|token| {
+                let executed = self.handlers[token.name](&mut state, &token.args);
+                (token, executed)
+            })
+            .collect();
+        Run { state, trace }
+    }
+}
@@ -0,0 +1,420 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The outcome of a successful search.
+///
+/// Besides the optimal cost, all optimal predecessors of every settled state
+/// are recorded, so one or all optimal paths can be reconstructed.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    /// Cost of the optimal paths
+    pub cost: C,
+    /// All goal states reached with the optimal cost
+    pub goals: Vec<S>,
+    /// Number of states taken from the queue and expanded
+    pub expanded: usize,
+    /// Number of distinct states ever put into the queue
+    pub discovered: usize,
+    states: Vec<S>,
+    predecessors: Vec<Vec<usize>>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Hash + Eq + Clone, C> SearchResult<S, C> {
+    /// One optimal path from the start to the first goal, both included
+    pub fn path(&self) -> Vec<S> {
+        let mut idx = self.index[&self.goals[0]];
+        let mut path = vec![self.states[idx].clone()];
+        while let Some(&pred) = self.predecessors[idx].first() {
+            idx = pred;
+            path.push(self.states[idx].clone());
+        }
+        path.reverse();
+        path
+    }
+
+    /// All optimal paths from the start to any of the goals.
+    ///
+    /// The number of paths can grow exponentially, see `states_on_optimal_paths`
+    /// if only the states are needed.
+    pub fn all_paths(&self) -> Vec<Vec<S>> {
+        let mut paths = Vec::new();
+        for goal in &self.goals {
+            let mut stack = vec![vec![self.index[goal]]];
+            while let Some(partial) = stack.pop() {
+                let last = *partial.last().unwrap();
+                if self.predecessors[last].is_empty() {
+                    paths.push(
+                        partial
+                            .iter()
+                            .rev()
+                            .map(|&idx| self.states[idx].clone())
+                            .collect(),
+                    );
+                    continue;
+                }
+                for &pred in &self.predecessors[last] {
+                    let mut next = partial.clone();
+                    next.push(pred);
+                    stack.push(next);
+                }
+            }
+        }
+        paths
+    }
+
+    /// All states lying on at least one optimal path
+    pub fn states_on_optimal_paths(&self) -> HashSet<S> {
+        let mut seen: HashSet<usize> = self.goals.iter().map(|g| self.index[g]).collect();
+        let mut stack: Vec<usize> = seen.iter().copied().collect();
+        while let Some(idx) = stack.pop() {
+            for &pred in &self.predecessors[idx] {
+                if seen.insert(pred) {
+                    stack.push(pred);
+                }
+            }
+        }
+        seen.into_iter()
+            .map(|idx| self.states[idx].clone())
+            .collect()
+    }
+}
+
+/// Interns states so the queues only have to deal with indices
+struct States<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Hash + Eq + Clone> States<S> {
+    fn new() -> Self {
+        States {
+            states: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Returns the index of the state and if it was newly added
+    fn intern(&mut self, state: S) -> (usize, bool) {
+        if let Some(&idx) = self.index.get(&state) {
+            return (idx, false);
+        }
+        let idx = self.states.len();
+        self.index.insert(state.clone(), idx);
+        self.states.push(state);
+        (idx, true)
+    }
+}
+
+/// Breadth first search where every step costs 1.
+///
+/// ```
+/// # use aoc24::search::bfs;
+/// // reach 10 from 1 by either adding one or doubling
+/// let res = bfs(1_u32, |&n| [n + 1, n * 2].into_iter().filter(|&m| m <= 10), |&n| n == 10)
+///     .unwrap();
+/// assert_eq!(res.cost, 4);
+/// assert_eq!(res.path(), vec![1, 2, 4, 5, 10]);
+/// assert_eq!(res.all_paths().len(), 2);
+/// ```
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, usize>>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut states = States::new();
+    let mut dist: Vec<usize> = Vec::new();
+    let mut predecessors: Vec<Vec<usize>> = Vec::new();
+    let mut goals = Vec::new();
+    let mut best: Option<usize> = None;
+    let mut expanded = 0;
+
+    let (start_idx, _) = states.intern(start);
+    dist.push(0);
+    predecessors.push(Vec::new());
+    let mut queue = VecDeque::from([start_idx]);
+
+    while let Some(idx) = queue.pop_front() {
+        let d = dist[idx];
+        if best.is_some_and(|b| d > b) {
+            break;
+        }
+        let state = states.states[idx].clone();
+        if is_goal(&state) {
+            best = Some(d);
+            goals.push(idx);
+            continue;
+        }
+        expanded += 1;
+        for next in successors(&state) {
+            let (next_idx, new) = states.intern(next);
+            if new {
+                dist.push(d + 1);
+                predecessors.push(vec![idx]);
+                queue.push_back(next_idx);
+            } else if dist[next_idx] == d + 1 {
+                predecessors[next_idx].push(idx);
+            }
+        }
+    }
+
+    let cost = best?;
+    Some(SearchResult {
+        cost,
+        goals: goals
+            .into_iter()
+            .map(|idx| states.states[idx].clone())
+            .collect(),
+        expanded,
+        discovered: states.states.len(),
+        states: states.states,
+        predecessors,
+        index: states.index,
+    })
+}
+
+/// Dijkstra's shortest path search for non-negative costs.
+///
+/// ```
+/// # use aoc24::search::dijkstra;
+/// let edges = [("a", "b", 7), ("a", "c", 2), ("c", "b", 3), ("b", "d", 1), ("c", "d", 9)];
+/// let res = dijkstra(
+///     "a",
+///     |&s| edges.iter().filter(move |e| e.0 == s).map(|e| (e.1, e.2)),
+///     |&s| s == "d",
+/// )
+/// .unwrap();
+/// assert_eq!(res.cost, 6);
+/// assert_eq!(res.path(), vec!["a", "c", "b", "d"]);
+///
+/// // zero cost edges are allowed, also when they form a cycle
+/// let edges = [(0, 1, 0), (1, 0, 0), (1, 2, 1)];
+/// let res = dijkstra(
+///     0,
+///     |&s| edges.iter().filter(move |e| e.0 == s).map(|e| (e.1, e.2)),
+///     |&s| s == 2,
+/// )
+/// .unwrap();
+/// assert_eq!(res.cost, 1);
+/// assert_eq!(res.path(), vec![0, 1, 2]);
+/// assert_eq!(res.all_paths(), vec![vec![0, 1, 2]]);
+///
+/// // optimal paths through already settled states are found in any edge order
+/// for edges in [
+///     [(0, 1, 1), (0, 2, 1), (2, 1, 0), (1, 3, 1)],
+///     [(0, 2, 1), (0, 1, 1), (2, 1, 0), (1, 3, 1)],
+/// ] {
+///     let res = dijkstra(
+///         0,
+///         |&s| edges.iter().filter(move |e| e.0 == s).map(|e| (e.1, e.2)),
+///         |&s| s == 3,
+///     )
+///     .unwrap();
+///     let mut paths = res.all_paths();
+///     paths.sort();
+///     assert_eq!(paths, vec![vec![0, 1, 3], vec![0, 2, 1, 3]]);
+/// }
+/// ```
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search, `heuristic` has to be admissible and consistent.
+///
+/// All optimal predecessors are recorded, except those that would close a
+/// cycle of zero cost edges.
+///
+/// ```
+/// # use aoc24::search::astar;
+/// # use aoc24::Location;
+/// let walls = [Location::new(1, 0), Location::new(1, 1), Location::new(1, 2)];
+/// let goal = Location::new(2, 0);
+/// let res = astar(
+///     Location::new(0, 0),
+///     |l| {
+///         [l.up(), l.down(), l.left(), l.right()]
+///             .into_iter()
+///             .filter(|n| n.is_inside_bounding_box(&Location::new(0, 0), &Location::new(3, 3)))
+///             .filter(|n| !walls.contains(n))
+///             .map(|n| (n, 1_u32))
+///     },
+///     |l| (l.row.abs_diff(goal.row) + l.col.abs_diff(goal.col)) as u32,
+///     |l| *l == goal,
+/// )
+/// .unwrap();
+/// assert_eq!(res.cost, 8);
+/// assert!(res.expanded <= res.discovered);
+/// ```
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut states = States::new();
+    let mut dist: Vec<C> = Vec::new();
+    let mut predecessors: Vec<Vec<usize>> = Vec::new();
+    let mut closed: Vec<bool> = Vec::new();
+    let mut goals = Vec::new();
+    let mut best: Option<C> = None;
+    let mut expanded = 0;
+
+    let h = heuristic(&start);
+    let (start_idx, _) = states.intern(start);
+    dist.push(C::default());
+    predecessors.push(Vec::new());
+    closed.push(false);
+    let mut heap = BinaryHeap::from([Reverse((h, C::default(), start_idx))]);
+
+    while let Some(Reverse((f, d, idx))) = heap.pop() {
+        if closed[idx] || d > dist[idx] {
+            continue;
+        }
+        if best.is_some_and(|b| f > b) {
+            break;
+        }
+        closed[idx] = true;
+        let state = states.states[idx].clone();
+        if is_goal(&state) {
+            best = Some(d);
+            goals.push(idx);
+            continue;
+        }
+        expanded += 1;
+        for (next, step) in successors(&state) {
+            let nd = d + step;
+            let (next_idx, new) = states.intern(next);
+            if new {
+                dist.push(nd);
+                predecessors.push(vec![idx]);
+                closed.push(false);
+            } else if nd == dist[next_idx] {
+                // only zero cost edges can close a cycle of predecessors
+                if nd != d || !is_ancestor(&predecessors, &dist, next_idx, idx) {
+                    predecessors[next_idx].push(idx);
+                }
+                continue;
+            } else if nd > dist[next_idx] || closed[next_idx] {
+                continue;
+            } else {
+                dist[next_idx] = nd;
+                predecessors[next_idx] = vec![idx];
+            }
+            let h = heuristic(&states.states[next_idx]);
+            heap.push(Reverse((nd + h, nd, next_idx)));
+        }
+    }
+
+    let cost = best?;
+    Some(SearchResult {
+        cost,
+        goals: goals
+            .into_iter()
+            .map(|idx| states.states[idx].clone())
+            .collect(),
+        expanded,
+        discovered: states.states.len(),
+        states: states.states,
+        predecessors,
+        index: states.index,
+    })
+}
+
+/// Whether `ancestor` is reachable from `idx` by following predecessors of
+/// the same distance
+fn is_ancestor<C: PartialEq>(
+    predecessors: &[Vec<usize>],
+    dist: &[C],
+    ancestor: usize,
+    idx: usize,
+) -> bool {
+    let mut seen = HashSet::from([idx]);
+    let mut stack = vec![idx];
+    while let Some(i) = stack.pop() {
+        if i == ancestor {
+            return true;
+        }
+        for &pred in &predecessors[i] {
+            if dist[pred] == dist[idx] && seen.insert(pred) {
+                stack.push(pred);
+            }
+        }
+    }
+    false
+}
+
+/// Count all distinct paths from `start` to any goal state.
+///
+/// A path ends at the first goal state it reaches. The state graph has to be
+/// acyclic, shared sub-paths are only counted once thanks to memoization.
+///
+/// ```
+/// # use aoc24::search::count_paths;
+/// # use aoc24::Location;
+/// // monotone lattice paths through a 3x3 grid
+/// let n = count_paths(
+///     Location::new(0, 0),
+///     |l| [l.down(), l.right()].into_iter().filter(|n| n.row <= 2 && n.col <= 2),
+///     |l| *l == Location::new(2, 2),
+/// );
+/// assert_eq!(n, 6);
+/// ```
+pub fn count_paths<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> u64
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut counts: HashMap<S, u64> = HashMap::new();
+    // iterative post-order DFS, so deep state graphs do not overflow the stack
+    let mut stack: Vec<(S, Option<Vec<S>>)> = vec![(start.clone(), None)];
+    while let Some((state, children)) = stack.pop() {
+        if counts.contains_key(&state) {
+            continue;
+        }
+        match children {
+            None => {
+                if is_goal(&state) {
+                    counts.insert(state, 1);
+                    continue;
+                }
+                let children: Vec<S> = successors(&state).into_iter().collect();
+                let pending: Vec<S> = children
+                    .iter()
+                    .filter(|c| !counts.contains_key(*c))
+                    .cloned()
+                    .collect();
+                stack.push((state, Some(children)));
+                stack.extend(pending.into_iter().map(|c| (c, None)));
+            }
+            Some(children) => {
+                let n = children.iter().map(|c| counts[c]).sum();
+                counts.insert(state, n);
+            }
+        }
+    }
+    counts[&start]
+}
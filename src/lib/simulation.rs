@@ -0,0 +1,224 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+use crate::VisualMap;
+
+/// A deterministic, step-wise simulation.
+pub trait Simulation: Clone {
+    /// Advance the simulation by one step
+    fn step(&mut self);
+
+    /// Whether the simulation has finished and must not be stepped any more
+    fn is_done(&self) -> bool {
+        false
+    }
+
+    /// A hash of the full state, used to detect repeated states
+    fn state_hash(&self) -> u64;
+
+    /// A picture of the current state, used for exporting frames
+    fn frame(&self) -> Option<VisualMap<char>> {
+        None
+    }
+}
+
+/// Hash any hashable value, as a convenience for implementing `Simulation::state_hash`
+pub fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Why `Simulator::run_until` stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The predicate became true
+    Reached,
+    /// The simulation reported that it is done
+    Done,
+    /// The state after `step` was already seen after `first_seen`
+    Repeated { first_seen: usize, step: usize },
+}
+
+/// Drives a `Simulation`, recording the history of states.
+///
+/// Full snapshots are only kept every `snapshot_interval` steps, other states
+/// are recomputed from the nearest snapshot when needed.
+///
+/// ```
+/// # use aoc24::simulation::{hash_of, RunOutcome, Simulation, Simulator};
+/// #[derive(Clone)]
+/// struct Counter(u32);
+///
+/// impl Simulation for Counter {
+///     fn step(&mut self) {
+///         self.0 = (self.0 + 3) % 10;
+///     }
+///     fn state_hash(&self) -> u64 {
+///         hash_of(&self.0)
+///     }
+/// }
+///
+/// let mut sim = Simulator::with_snapshot_interval(Counter(0), 4);
+/// assert_eq!(sim.run_until(|c| c.0 == 1), RunOutcome::Reached);
+/// assert_eq!(sim.steps(), 7);
+/// assert_eq!(sim.state_at(2).0, 6);
+/// sim.rewind(3);
+/// assert_eq!((sim.steps(), sim.current().0), (4, 2));
+/// assert_eq!(sim.run(), RunOutcome::Repeated { first_seen: 0, step: 10 });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simulator<S> {
+    current: S,
+    steps: usize,
+    snapshot_interval: usize,
+    snapshots: BTreeMap<usize, S>,
+    hashes: Vec<u64>,
+    seen: HashMap<u64, usize>,
+}
+
+impl<S: Simulation> Simulator<S> {
+    /// Create a driver that keeps a snapshot of every step
+    pub fn new(initial: S) -> Self {
+        Self::with_snapshot_interval(initial, 1)
+    }
+
+    /// Create a driver that keeps a snapshot every `interval` steps
+    pub fn with_snapshot_interval(initial: S, interval: usize) -> Self {
+        assert!(interval > 0, "snapshot interval must be positive");
+        let hash = initial.state_hash();
+        Simulator {
+            snapshots: BTreeMap::from([(0, initial.clone())]),
+            current: initial,
+            steps: 0,
+            snapshot_interval: interval,
+            hashes: vec![hash],
+            seen: HashMap::from([(hash, 0)]),
+        }
+    }
+
+    #[inline(always)]
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    /// Number of steps taken so far
+    #[inline(always)]
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Take one step, returns `false` if the simulation is already done
+    pub fn step(&mut self) -> bool {
+        self.step_checked().is_some()
+    }
+
+    /// Take one step, returning the step the new state was first seen at
+    fn step_checked(&mut self) -> Option<Option<usize>> {
+        if self.current.is_done() {
+            return None;
+        }
+        self.current.step();
+        self.steps += 1;
+        if self.steps.is_multiple_of(self.snapshot_interval) {
+            self.snapshots.insert(self.steps, self.current.clone());
+        }
+        let hash = self.current.state_hash();
+        self.hashes.push(hash);
+        Some(match self.seen.get(&hash) {
+            Some(&first_seen) => Some(first_seen),
+            None => {
+                self.seen.insert(hash, self.steps);
+                None
+            }
+        })
+    }
+
+    /// Step until `pred` holds for the current state, the simulation is done or
+    /// a state repeats.
+    pub fn run_until(&mut self, mut pred: impl FnMut(&S) -> bool) -> RunOutcome {
+        loop {
+            if pred(&self.current) {
+                return RunOutcome::Reached;
+            }
+            match self.step_checked() {
+                None => return RunOutcome::Done,
+                Some(Some(first_seen)) => {
+                    return RunOutcome::Repeated {
+                        first_seen,
+                        step: self.steps,
+                    }
+                }
+                Some(None) => {}
+            }
+        }
+    }
+
+    /// Step until the simulation is done or a state repeats
+    pub fn run(&mut self) -> RunOutcome {
+        self.run_until(|_| false)
+    }
+
+    /// The state after `step` steps, recomputed from the nearest snapshot
+    pub fn state_at(&self, step: usize) -> S {
+        assert!(step <= self.steps, "step {} is in the future", step);
+        let (&from, snapshot) = self.snapshots.range(..=step).next_back().unwrap();
+        let mut state = snapshot.clone();
+        for _ in from..step {
+            state.step();
+        }
+        state
+    }
+
+    /// Go back `n` steps, forgetting everything after that
+    pub fn rewind(&mut self, n: usize) {
+        let target = self.steps.saturating_sub(n);
+        self.current = self.state_at(target);
+        self.steps = target;
+        self.snapshots.split_off(&(target + 1));
+        for hash in self.hashes.drain(target + 1..) {
+            if self.seen.get(&hash).is_some_and(|&s| s > target) {
+                self.seen.remove(&hash);
+            }
+        }
+    }
+
+    /// Rendered frames of every step from the start up to now
+    ///
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// # use aoc24::simulation::{hash_of, Simulation, Simulator};
+    /// # use aoc24::{Location, VisualMap};
+    /// #[derive(Clone)]
+    /// struct Walker(Location);
+    ///
+    /// impl Simulation for Walker {
+    ///     fn step(&mut self) {
+    ///         self.0 = self.0.right();
+    ///     }
+    ///     fn is_done(&self) -> bool {
+    ///         self.0.col == 2
+    ///     }
+    ///     fn state_hash(&self) -> u64 {
+    ///         hash_of(&self.0)
+    ///     }
+    ///     fn frame(&self) -> Option<VisualMap<char>> {
+    ///         let map = BTreeMap::from([(self.0, '@')]);
+    ///         Some(VisualMap { map, max: Location::new(0, 2) })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulator::new(Walker(Location::new(0, 0)));
+    /// sim.run();
+    /// assert_eq!(sim.frames().collect::<Vec<_>>(), vec!["@..\n", ".@.\n", "..@\n"]);
+    /// ```
+    pub fn frames(&self) -> impl Iterator<Item = String> + '_ {
+        (0..=self.steps).filter_map(|step| self.frame_at(step))
+    }
+
+    /// The rendered frame after `step` steps, if the simulation provides frames
+    pub fn frame_at(&self, step: usize) -> Option<String> {
+        self.state_at(step).frame().map(|map| map.to_string())
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Node {
+    children: BTreeMap<u8, usize>,
+    terminal: bool,
+}
+
+/// A prefix tree of byte strings, used to split strings into known patterns.
+///
+/// ```
+/// # use aoc24::trie::Trie;
+/// let trie: Trie = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"].into_iter().collect();
+/// let designs = ["brwrr", "bggr", "gbbr", "rrbgbr", "ubwu", "bwurrg", "brgr", "bbrgwb"];
+/// let counts: Vec<Option<u128>> = designs.iter().map(|d| trie.compose(d)).collect();
+/// assert_eq!(counts.iter().filter(|c| c.is_some()).count(), 6);
+/// assert_eq!(counts.iter().flatten().sum::<u128>(), 16);
+/// assert_eq!(counts[2], Some(4));
+/// assert_eq!(counts[4], None);
+/// assert_eq!(trie.explain("rrbgbr"), Some(vec!["r", "r", "b", "g", "b", "r"]));
+/// assert_eq!(trie.explain("bbrgwb"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trie {
+    nodes: Vec<Node>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Trie::new()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for Trie {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut trie = Trie::new();
+        for pattern in iter {
+            trie.insert(pattern.as_ref());
+        }
+        trie
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Trie {
+            nodes: vec![Node::default()],
+        }
+    }
+
+    pub fn insert(&mut self, pattern: &str) {
+        let mut node = 0;
+        for b in pattern.bytes() {
+            node = match self.nodes[node].children.get(&b) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(b, child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].terminal = true;
+    }
+
+    pub fn contains(&self, pattern: &str) -> bool {
+        let mut node = 0;
+        for b in pattern.bytes() {
+            match self.nodes[node].children.get(&b) {
+                Some(&child) => node = child,
+                None => return false,
+            }
+        }
+        self.nodes[node].terminal
+    }
+
+    /// Lengths of all patterns that are a prefix of `s`, in increasing order
+    pub fn prefix_lengths<'a>(&'a self, s: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let mut node = Some(0);
+        (0..=s.len()).filter_map(move |len| {
+            let current = node?;
+            node = s
+                .get(len)
+                .and_then(|b| self.nodes[current].children.get(b).copied());
+            self.nodes[current].terminal.then_some(len)
+        })
+    }
+
+    /// Number of ways to write `design` as a sequence of patterns, or `None`
+    /// if there is none. Saturates at `u128::MAX`.
+    ///
+    /// ```
+    /// # use aoc24::trie::Trie;
+    /// let trie: Trie = ["a", "aa"].into_iter().collect();
+    /// assert_eq!(trie.compose(""), Some(1));
+    /// assert_eq!(trie.compose("aaaa"), Some(5));
+    /// assert_eq!(trie.compose(&"a".repeat(200)), Some(u128::MAX));
+    /// ```
+    pub fn compose(&self, design: &str) -> Option<u128> {
+        let ways = self.ways(design.as_bytes());
+        (ways[0] > 0).then_some(ways[0])
+    }
+
+    /// `ways[i]` is the number of ways to write `design[i..]` as patterns
+    fn ways(&self, design: &[u8]) -> Vec<u128> {
+        let n = design.len();
+        let mut ways = vec![0_u128; n + 1];
+        ways[n] = 1;
+        for i in (0..n).rev() {
+            ways[i] = self
+                .prefix_lengths(&design[i..])
+                .filter(|len| *len > 0)
+                .fold(0_u128, |acc, len| acc.saturating_add(ways[i + len]));
+        }
+        ways
+    }
+
+    /// One way to write `design` as patterns, preferring shorter patterns first
+    pub fn explain<'a>(&self, design: &'a str) -> Option<Vec<&'a str>> {
+        let bytes = design.as_bytes();
+        let ways = self.ways(bytes);
+        if ways[0] == 0 {
+            return None;
+        }
+        let mut parts = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let len = self
+                .prefix_lengths(&bytes[i..])
+                .find(|len| *len > 0 && ways[i + len] > 0)?;
+            parts.push(&design[i..i + len]);
+            i += len;
+        }
+        Some(parts)
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Location, VisualMap};
+
+/// Disjoint-set forest with path compression and union by rank.
+///
+/// Keys are added on first use, each starting out as its own component.
+///
+/// ```
+/// # use aoc24::union_find::UnionFind;
+/// let mut uf = UnionFind::new();
+/// uf.union(&1, &2);
+/// uf.union(&3, &4);
+/// assert!(uf.connected(&1, &2));
+/// assert!(!uf.connected(&2, &3));
+/// uf.union(&2, &3);
+/// assert!(uf.connected(&1, &4));
+/// assert_eq!(uf.component_size(&4), 4);
+/// assert_eq!(uf.component_count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnionFind<K> {
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl<K: Hash + Eq + Clone> Default for UnionFind<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone> UnionFind<K> {
+    pub fn new() -> Self {
+        UnionFind {
+            index: HashMap::new(),
+            keys: Vec::new(),
+            parent: Vec::new(),
+            rank: Vec::new(),
+            size: Vec::new(),
+            components: 0,
+        }
+    }
+
+    /// Add `key` as a singleton component if it is not known yet, returning its index
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&idx) = self.index.get(&key) {
+            return idx;
+        }
+        let idx = self.keys.len();
+        self.index.insert(key.clone(), idx);
+        self.keys.push(key);
+        self.parent.push(idx);
+        self.rank.push(0);
+        self.size.push(1);
+        self.components += 1;
+        idx
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Number of keys
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Number of disjoint components
+    #[inline(always)]
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    fn find_idx(&mut self, idx: usize) -> usize {
+        let mut root = idx;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut cur = idx;
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    /// The representative key of the component containing `key`
+    pub fn find(&mut self, key: &K) -> Option<K> {
+        let idx = *self.index.get(key)?;
+        let root = self.find_idx(idx);
+        Some(self.keys[root].clone())
+    }
+
+    /// Merge the components of `a` and `b`, adding them if needed.
+    ///
+    /// Returns `false` if they already were in the same component.
+    pub fn union(&mut self, a: &K, b: &K) -> bool {
+        let a = self.insert(a.clone());
+        let b = self.insert(b.clone());
+        let (a, b) = (self.find_idx(a), self.find_idx(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.rank[a] >= self.rank[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        if self.rank[big] == self.rank[small] {
+            self.rank[big] += 1;
+        }
+        self.components -= 1;
+        true
+    }
+
+    /// Check if `a` and `b` are known and in the same component
+    pub fn connected(&mut self, a: &K, b: &K) -> bool {
+        match (self.index.get(a), self.index.get(b)) {
+            (Some(&a), Some(&b)) => self.find_idx(a) == self.find_idx(b),
+            _ => false,
+        }
+    }
+
+    /// Number of keys in the component containing `key`, 0 for unknown keys
+    pub fn component_size(&mut self, key: &K) -> usize {
+        match self.index.get(key) {
+            Some(&idx) => {
+                let root = self.find_idx(idx);
+                self.size[root]
+            }
+            None => 0,
+        }
+    }
+
+    /// All components, each as the list of its keys in insertion order
+    pub fn components(&mut self) -> Vec<Vec<K>> {
+        let mut by_root: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<K>> = Vec::new();
+        for idx in 0..self.keys.len() {
+            let root = self.find_idx(idx);
+            let component = *by_root.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[component].push(self.keys[idx].clone());
+        }
+        components
+    }
+}
+
+impl<K: Hash + Eq + Clone> FromIterator<K> for UnionFind<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut uf = UnionFind::new();
+        for key in iter {
+            uf.insert(key);
+        }
+        uf
+    }
+}
+
+impl<T: PartialEq> VisualMap<T> {
+    /// Union all horizontally or vertically adjacent locations with equal values.
+    ///
+    /// ```
+    /// let map = aoc24::read_visual_map("AAAA\nBBCD\nBBCC\nEEEC".lines().map(String::from));
+    /// let mut uf = map.union_find();
+    /// assert_eq!(uf.component_count(), 5);
+    /// assert_eq!(uf.component_size(&aoc24::Location::new(1, 2)), 4);
+    /// ```
+    pub fn union_find(&self) -> UnionFind<Location> {
+        let mut uf: UnionFind<Location> = self.map.keys().cloned().collect();
+        for (loc, value) in self.map.iter() {
+            for other in [loc.right(), loc.down()] {
+                if self.map.get(&other) == Some(value) {
+                    uf.union(loc, &other);
+                }
+            }
+        }
+        uf
+    }
+}
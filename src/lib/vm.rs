@@ -0,0 +1,295 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use itertools::Itertools;
+
+/// Instructions a candidate in `Computer::find_quine` may run before it is rejected
+pub const QUINE_MAX_STEPS: usize = 100_000;
+
+/// The instructions of the 3-bit computer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    pub fn from_u8(op: u8) -> Option<Opcode> {
+        use Opcode::*;
+        [Adv, Bxl, Bst, Jnz, Bxc, Out, Bdv, Cdv]
+            .get(op as usize)
+            .copied()
+    }
+
+    /// Whether the operand is a combo operand instead of a literal
+    pub fn has_combo_operand(&self) -> bool {
+        use Opcode::*;
+        matches!(self, Adv | Bst | Out | Bdv | Cdv)
+    }
+}
+
+/// Register names, in the order they are stored
+const REGISTERS: [&str; 3] = ["a", "b", "c"];
+
+/// Name a combo operand, either a literal or a register
+fn combo_name(operand: u8) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4..=6 => REGISTERS[operand as usize - 4].to_string(),
+        _ => "<invalid>".to_string(),
+    }
+}
+
+/// One executed instruction, with the registers before executing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub ip: usize,
+    pub opcode: Opcode,
+    pub operand: u8,
+    pub registers: [u64; 3],
+    pub output: Option<u8>,
+}
+
+/// The result of running a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub output: Vec<u8>,
+    pub registers: [u64; 3],
+    /// Every executed instruction, only filled when tracing
+    pub trace: Vec<Step>,
+    /// `false` if the step limit was reached first
+    pub halted: bool,
+}
+
+/// Format an output as comma separated values
+pub fn format_output(output: &[u8]) -> String {
+    output.iter().join(",")
+}
+
+/// A computer with three registers and a program of 3-bit numbers.
+///
+/// ```
+/// # use aoc24::vm::{format_output, Computer};
+/// let computer: Computer = "\
+/// Register A: 729
+/// Register B: 0
+/// Register C: 0
+///
+/// Program: 0,1,5,4,3,0"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(format_output(&computer.output()), "4,6,3,5,6,3,5,2,1,0");
+/// let run = computer.run_traced(100);
+/// assert!(run.halted);
+/// assert_eq!(run.trace.len(), 30);
+/// assert_eq!(run.trace[2].registers, [364, 0, 0]);
+/// assert_eq!(computer.disassemble(), vec![
+///     "0: a = a >> 1",
+///     "2: out a % 8",
+///     "4: if a != 0 jump 0",
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Computer {
+    pub registers: [u64; 3],
+    pub program: Vec<u8>,
+}
+
+impl FromStr for Computer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut registers = [0; 3];
+        let mut program = None;
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, value) = line.split_once(':').context("missing ':'")?;
+            match name.trim() {
+                "Register A" => registers[0] = value.trim().parse()?,
+                "Register B" => registers[1] = value.trim().parse()?,
+                "Register C" => registers[2] = value.trim().parse()?,
+                "Program" => {
+                    program = Some(
+                        value
+                            .trim()
+                            .split(',')
+                            .map(|v| match v.trim().parse()? {
+                                v @ 0..=7 => Ok(v),
+                                v => bail!("{} is not a 3-bit number", v),
+                            })
+                            .collect::<anyhow::Result<_>>()?,
+                    )
+                }
+                name => bail!("unknown line {:?}", name),
+            }
+        }
+        Ok(Computer {
+            registers,
+            program: program.context("missing program")?,
+        })
+    }
+}
+
+impl Computer {
+    /// The same program with register A set to `a`
+    pub fn with_a(&self, a: u64) -> Computer {
+        let mut computer = self.clone();
+        computer.registers[0] = a;
+        computer
+    }
+
+    fn combo(registers: &[u64; 3], operand: u8) -> u64 {
+        match operand {
+            0..=3 => operand as u64,
+            4..=6 => registers[operand as usize - 4],
+            _ => panic!("invalid combo operand {}", operand),
+        }
+    }
+
+    /// Run for at most `max_steps` instructions, recording a trace if `trace` is set
+    fn execute(&self, max_steps: usize, trace: bool) -> Run {
+        let mut r = self.registers;
+        let mut ip = 0;
+        let mut output = vec![];
+        let mut steps = vec![];
+        for _ in 0..max_steps {
+            let (Some(&op), Some(&operand)) = (self.program.get(ip), self.program.get(ip + 1))
+            else {
+                return Run {
+                    output,
+                    registers: r,
+                    trace: steps,
+                    halted: true,
+                };
+            };
+            let opcode = Opcode::from_u8(op).expect("program contains 3-bit numbers");
+            let before = r;
+            let mut out = None;
+            let mut next = ip + 2;
+            let div = |r: &[u64; 3]| {
+                let shift = Self::combo(r, operand);
+                r[0].checked_shr(shift.try_into().unwrap_or(u32::MAX))
+                    .unwrap_or(0)
+            };
+            match opcode {
+                Opcode::Adv => r[0] = div(&r),
+                Opcode::Bxl => r[1] ^= operand as u64,
+                Opcode::Bst => r[1] = Self::combo(&r, operand) % 8,
+                Opcode::Jnz => {
+                    if r[0] != 0 {
+                        next = operand as usize;
+                    }
+                }
+                Opcode::Bxc => r[1] ^= r[2],
+                Opcode::Out => out = Some((Self::combo(&r, operand) % 8) as u8),
+                Opcode::Bdv => r[1] = div(&r),
+                Opcode::Cdv => r[2] = div(&r),
+            }
+            output.extend(out);
+            if trace {
+                steps.push(Step {
+                    ip,
+                    opcode,
+                    operand,
+                    registers: before,
+                    output: out,
+                });
+            }
+            ip = next;
+        }
+        Run {
+            output,
+            registers: r,
+            trace: steps,
+            halted: false,
+        }
+    }
+
+    /// Run until the program halts, which might never happen
+    pub fn output(&self) -> Vec<u8> {
+        self.execute(usize::MAX, false).output
+    }
+
+    /// Run for at most `max_steps` instructions, recording every instruction
+    pub fn run_traced(&self, max_steps: usize) -> Run {
+        self.execute(max_steps, true)
+    }
+
+    /// Describe each instruction as pseudocode, prefixed by its address
+    pub fn disassemble(&self) -> Vec<String> {
+        self.program
+            .chunks(2)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let (op, operand) = (chunk[0], chunk.get(1).copied().unwrap_or(0));
+                let combo = combo_name(operand);
+                let code = match Opcode::from_u8(op) {
+                    Some(Opcode::Adv) => format!("a = a >> {}", combo),
+                    Some(Opcode::Bxl) => format!("b = b ^ {}", operand),
+                    Some(Opcode::Bst) => format!("b = {} % 8", combo),
+                    Some(Opcode::Jnz) => format!("if a != 0 jump {}", operand),
+                    Some(Opcode::Bxc) => "b = b ^ c".to_string(),
+                    Some(Opcode::Out) => format!("out {} % 8", combo),
+                    Some(Opcode::Bdv) => format!("b = a >> {}", combo),
+                    Some(Opcode::Cdv) => format!("c = a >> {}", combo),
+                    None => format!("<invalid opcode {}>", op),
+                };
+                format!("{}: {}", i * 2, code)
+            })
+            .collect()
+    }
+
+    /// The smallest value of register A that makes the program output itself.
+    ///
+    /// Assumes the program is a loop that outputs one value and shifts A right
+    /// by 3 bits per iteration, so the last output only depends on the highest
+    /// 3 bits of A. The search fixes A 3 bits at a time from the highest bits,
+    /// keeping every candidate that produces the right tail of the program.
+    /// Candidates that do not halt within `QUINE_MAX_STEPS` instructions are
+    /// rejected.
+    ///
+    /// ```
+    /// # use aoc24::vm::Computer;
+    /// let computer: Computer = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(computer.find_quine(), Some(117440));
+    /// assert_eq!(computer.with_a(117440).output(), computer.program);
+    ///
+    /// // loops forever for every non-zero A
+    /// let computer: Computer = "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(computer.find_quine(), None);
+    /// ```
+    pub fn find_quine(&self) -> Option<u64> {
+        let mut candidates = vec![0_u64];
+        for i in (0..self.program.len()).rev() {
+            candidates = candidates
+                .into_iter()
+                .flat_map(|a| (0..8).map(move |bits| a << 3 | bits))
+                .filter(|&a| {
+                    let run = self.with_a(a).execute(QUINE_MAX_STEPS, false);
+                    run.halted && run.output == self.program[i..]
+                })
+                .collect();
+        }
+        candidates.into_iter().min()
+    }
+}
+
+impl fmt::Display for Computer {
+    /// Print the disassembled program, one instruction per line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.disassemble() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
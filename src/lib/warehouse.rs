@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, HashSet};
+use std::rc::Rc;
+
+use crate::simulation::{hash_of, Simulation};
+use crate::{parse_map_and_lines, Direction, Location, VisualMap};
+
+/// Parse a move drawn as one of `^`, `>`, `v` or `<`
+pub fn parse_move(c: char) -> Option<Direction> {
+    match c {
+        '^' => Some(Direction::Up),
+        '>' => Some(Direction::Right),
+        'v' => Some(Direction::Down),
+        '<' => Some(Direction::Left),
+        _ => None,
+    }
+}
+
+/// A robot pushing boxes around a warehouse, following a list of moves.
+///
+/// Boxes are either single cells `O` or two cells wide `[]`, walls are `#`.
+///
+/// ```
+/// # use aoc24::warehouse::Warehouse;
+/// let map = ["########", "#..O.O.#", "##@.O..#", "#...O..#", "#.#.O..#", "#...O..#", "#......#", "########"];
+/// let input = map.iter().chain(&["", "<^^>>>vv<v>>v<<"]).map(|s| s.to_string());
+/// let mut warehouse = Warehouse::parse(input).unwrap();
+/// warehouse.run();
+/// assert_eq!(warehouse.gps_sum(), 2028);
+/// assert_eq!(
+///     warehouse.to_string().lines().collect::<Vec<_>>(),
+///     ["########", "#....OO#", "##.....#", "#.....O#", "#.#O@..#", "#...O..#", "#...O..#", "########"]
+/// );
+///
+/// let map = ["#######", "#...#.#", "#.....#", "#..OO@#", "#..O..#", "#.....#", "#######"];
+/// let input = map.iter().chain(&["", "<vv<<^^<<^^"]).map(|s| s.to_string());
+/// let mut warehouse = Warehouse::parse(input).unwrap().widened();
+/// warehouse.run();
+/// assert_eq!(
+///     warehouse.to_string().lines().collect::<Vec<_>>(),
+///     [
+///         "##############",
+///         "##...[].##..##",
+///         "##...@.[]...##",
+///         "##....[]....##",
+///         "##..........##",
+///         "##..........##",
+///         "##############",
+///     ]
+/// );
+/// assert_eq!(warehouse.gps_sum(), 618);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Warehouse {
+    /// The map without the robot
+    map: VisualMap<char>,
+    robot: Location,
+    moves: Rc<[Direction]>,
+    /// Index of the next move
+    next: usize,
+}
+
+impl Warehouse {
+    /// Create a warehouse from a map containing the robot as `@`
+    pub fn new(mut map: VisualMap<char>, moves: Vec<Direction>) -> Option<Self> {
+        let robot = *map.map.iter().find(|(_, c)| **c == '@')?.0;
+        map.map.insert(robot, '.');
+        Some(Warehouse {
+            map,
+            robot,
+            moves: moves.into(),
+            next: 0,
+        })
+    }
+
+    /// Parse the map and the moves below it, ignoring unknown characters in the moves
+    pub fn parse(lines: impl Iterator<Item = String>) -> Option<Self> {
+        let (map, moves) = parse_map_and_lines(lines, |line| {
+            line.chars().filter_map(parse_move).collect::<Vec<_>>()
+        });
+        Warehouse::new(map, moves.concat())
+    }
+
+    /// The same warehouse with everything but the robot twice as wide
+    pub fn widened(&self) -> Self {
+        let mut map = BTreeMap::new();
+        for (loc, c) in &self.map.map {
+            let (left, right) = match c {
+                'O' => ('[', ']'),
+                c => (*c, *c),
+            };
+            map.insert(Location::new(loc.row, loc.col * 2), left);
+            map.insert(Location::new(loc.row, loc.col * 2 + 1), right);
+        }
+        Warehouse {
+            map: VisualMap {
+                map,
+                max: Location::new(self.map.max.row, self.map.max.col * 2 + 1),
+            },
+            robot: Location::new(self.robot.row, self.robot.col * 2),
+            moves: self.moves.clone(),
+            next: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn robot(&self) -> Location {
+        self.robot
+    }
+
+    /// All box cells that would be pushed by moving in `dir`, ordered from the
+    /// robot outwards, or `None` if a wall is in the way
+    pub fn pushed(&self, dir: Direction) -> Option<Vec<Location>> {
+        let mut pushed = vec![];
+        let mut seen = HashSet::new();
+        let mut queue = vec![self.robot.apply(dir)];
+        let mut i = 0;
+        while i < queue.len() {
+            let loc = queue[i];
+            i += 1;
+            let cells = match self.map.map.get(&loc) {
+                Some('#') => return None,
+                Some('O') => vec![loc],
+                Some('[') => vec![loc, loc.right()],
+                Some(']') => vec![loc, loc.left()],
+                _ => vec![],
+            };
+            for cell in cells {
+                if seen.insert(cell) {
+                    pushed.push(cell);
+                    queue.push(cell.apply(dir));
+                }
+            }
+        }
+        Some(pushed)
+    }
+
+    /// Move the robot in `dir` if nothing blocks it, returns whether it moved
+    pub fn push(&mut self, dir: Direction) -> bool {
+        let Some(pushed) = self.pushed(dir) else {
+            return false;
+        };
+        let boxes: Vec<(Location, char)> =
+            pushed.iter().map(|loc| (*loc, self.map.map[loc])).collect();
+        for (loc, _) in &boxes {
+            self.map.map.insert(*loc, '.');
+        }
+        for (loc, c) in boxes {
+            self.map.map.insert(loc.apply(dir), c);
+        }
+        self.robot = self.robot.apply(dir);
+        true
+    }
+
+    /// Follow all remaining moves
+    pub fn run(&mut self) {
+        while !self.is_done() {
+            self.step();
+        }
+    }
+
+    /// Sum of `100 * row + col` of the left edge of every box
+    pub fn gps_sum(&self) -> isize {
+        self.map
+            .map
+            .iter()
+            .filter(|(_, c)| matches!(c, 'O' | '['))
+            .map(|(loc, _)| 100 * loc.row + loc.col)
+            .sum()
+    }
+
+    /// The map with the robot drawn as `@`
+    pub fn map(&self) -> VisualMap<char> {
+        let mut map = self.map.clone();
+        map.map.insert(self.robot, '@');
+        map
+    }
+}
+
+impl Simulation for Warehouse {
+    fn step(&mut self) {
+        let dir = self.moves[self.next];
+        self.next += 1;
+        self.push(dir);
+    }
+
+    fn is_done(&self) -> bool {
+        self.next >= self.moves.len()
+    }
+
+    fn state_hash(&self) -> u64 {
+        hash_of(&(&self.map.map, self.robot, self.next))
+    }
+
+    fn frame(&self) -> Option<VisualMap<char>> {
+        Some(self.map())
+    }
+}
+
+impl std::fmt::Display for Warehouse {
+    /// Render the map with the robot, for stepping through moves use the
+    /// frames of a `Simulator`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.map())
+    }
+}